@@ -0,0 +1,109 @@
+use cosmwasm_std::{Deps, Uint128, Decimal, Coin, from_slice, to_vec};
+use serde::de::IgnoredAny;
+use std::str::FromStr;
+
+use smartwallet::wallet::{ActionTemplate, ActionParam, ActionParamType};
+use crate::error::ContractError;
+
+/// ids below this are reserved for the hardwired hot msgs in contract.rs, which the registry doesn't replace
+pub const FIRST_ACTION_TEMPLATE_ID: u64 = 1000u64;
+
+pub fn validate_template(template: &ActionTemplate) -> Result<(), ContractError> {
+
+    //template must be valid json with the placeholders left in
+    if from_slice::<IgnoredAny>(template.msg.as_bytes()).is_err(){
+        return Err(ContractError::InvalidActionTemplate{reason: String::from("msg is not valid json")});
+    }
+
+    for (i, slot) in template.params.iter().enumerate(){
+        if slot.name.is_empty() || template.params.iter().skip(i + 1).any(|x| x.name == slot.name){
+            return Err(ContractError::InvalidActionTemplate{reason: format!("bad param name {}", slot.name)});
+        }
+    }
+
+    //funds amount has to come from a uint128 slot
+    if let Some(funds) = &template.funds{
+        if !template.params.iter().any(|x| x.name == funds.amount_param && x.param_type == ActionParamType::Uint128){
+            return Err(ContractError::InvalidActionTemplate{reason: String::from("funds amount_param must be a uint128 param")});
+        }
+    }
+
+    Ok(())
+}
+
+/// fills the template's placeholders and returns the msg json plus any attached funds
+pub fn render_action(
+    deps: Deps,
+    template: &ActionTemplate,
+    params: &[ActionParam],
+) -> Result<(String, Vec<Coin>), ContractError> {
+
+    //every param must match a slot
+    if let Some(unknown) = params.iter().find(|&x| !template.params.iter().any(|slot| slot.name == x.name)){
+        return Err(ContractError::InvalidActionParam{name: unknown.name.clone()});
+    }
+
+    let mut rendered: Vec<(String, String)> = vec![];
+
+    for slot in template.params.iter(){
+        let value = match params.iter().find(|&x| x.name == slot.name){
+            Some(param) => &param.value,
+            None => return Err(ContractError::InvalidActionParam{name: slot.name.clone()}),
+        };
+
+        let invalid = || ContractError::InvalidActionParam{name: slot.name.clone()};
+
+        //values are typed so a param can never break out of its slot
+        let value = match slot.param_type{
+            ActionParamType::Uint128 => format!("\"{}\"", Uint128::from(u128::from_str(value).map_err(|_| invalid())?)),
+            ActionParamType::Decimal => format!("\"{}\"", Decimal::from_str(value).map_err(|_| invalid())?),
+            ActionParamType::U64 => u64::from_str(value).map_err(|_| invalid())?.to_string(),
+            ActionParamType::String => String::from_utf8(to_vec(value)?).map_err(|_| invalid())?,
+            ActionParamType::Address => format!("\"{}\"", deps.api.addr_validate(value).map_err(|_| invalid())?),
+        };
+
+        rendered.push((format!("\"${}\"", slot.name), value));
+    }
+
+    //one pass over the template, so a value that looks like a placeholder is never filled in again
+    let mut msg = String::with_capacity(template.msg.len());
+    let mut rest = template.msg.as_str();
+    while let Some(start) = rest.find("\"$"){
+        msg.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match rendered.iter().find(|(placeholder, _)| rest.starts_with(placeholder.as_str())){
+            Some((placeholder, value)) => {
+                msg.push_str(value);
+                rest = &rest[placeholder.len()..];
+            },
+            None => {
+                msg.push('"');
+                rest = &rest[1..];
+            },
+        }
+    }
+    msg.push_str(rest);
+
+    let funds = match &template.funds{
+        Some(funds) => {
+            let amount = params.iter()
+                .find(|&x| x.name == funds.amount_param)
+                .map(|x| u128::from_str(&x.value).unwrap_or_default())
+                .map(Uint128::from)
+                .unwrap_or_default();
+
+            if amount > funds.max_amount{
+                return Err(ContractError::ActionFundsExceeded{});
+            }
+
+            if amount.is_zero(){
+                vec![]
+            } else {
+                vec![Coin{denom: funds.denom.clone(), amount}]
+            }
+        },
+        None => vec![],
+    };
+
+    Ok((msg, funds))
+}
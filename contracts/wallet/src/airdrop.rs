@@ -0,0 +1,12 @@
+use cosmwasm_std::Decimal;
+
+use smartwallet::wallet::Airdrop;
+use crate::error::ContractError;
+
+pub fn validate_airdrop(airdrop: &Airdrop) -> Result<(), ContractError> {
+    if airdrop.max_spread >= Decimal::one(){
+        return Err(ContractError::InvalidAirdrop{reason: String::from("max_spread must be below 1")});
+    }
+
+    Ok(())
+}
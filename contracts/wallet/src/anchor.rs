@@ -0,0 +1,100 @@
+use cosmwasm_std::{QuerierWrapper, StdResult, Uint128, Decimal};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use terra_cosmwasm::TerraQuerier;
+use std::cmp::min;
+
+use moneymarket::market::{QueryMsg as MarketQueryMsg, BorrowerInfoResponse, EpochStateResponse};
+use moneymarket::overseer::{QueryMsg as OverseerQueryMsg, CollateralsResponse};
+use moneymarket::oracle::{QueryMsg as OracleQueryMsg, PriceResponse};
+
+use smartwallet::wallet::LoanConfig;
+use crate::error::ContractError;
+
+pub fn validate_loan_config(loan_config: &LoanConfig) -> Result<(), ContractError> {
+    if loan_config.target_ltv.is_zero() || loan_config.target_ltv >= Decimal256::one(){
+        return Err(ContractError::InvalidLoanConfig{reason: String::from("target_ltv must be between 0 and 1")});
+    }
+    if loan_config.safe_ltv.is_zero() || loan_config.safe_ltv > loan_config.target_ltv{
+        return Err(ContractError::InvalidLoanConfig{reason: String::from("safe_ltv must be between 0 and target_ltv")});
+    }
+    Ok(())
+}
+
+/// outstanding loan including interest accrued up to block_height
+pub fn query_loan_amount(querier: &QuerierWrapper, market: &str, borrower: &str, block_height: u64) -> StdResult<Uint256> {
+    let borrower_info: BorrowerInfoResponse = querier.query_wasm_smart(market, &MarketQueryMsg::BorrowerInfo{
+        borrower: borrower.to_string(),
+        block_height: Some(block_height),
+    })?;
+    Ok(borrower_info.loan_amount)
+}
+
+/// uusd value of every collateral the overseer has locked for borrower, at oracle prices
+pub fn query_collateral_value(querier: &QuerierWrapper, overseer: &str, oracle: &str, borrower: &str) -> StdResult<Uint256> {
+    let collaterals: CollateralsResponse = querier.query_wasm_smart(overseer, &OverseerQueryMsg::Collaterals{
+        borrower: borrower.to_string(),
+    })?;
+
+    collaterals.collaterals.iter().try_fold(Uint256::zero(), |acc, (token, amount)| {
+        Ok(acc + *amount * query_price(querier, oracle, token)?)
+    })
+}
+
+pub fn query_price(querier: &QuerierWrapper, oracle: &str, asset: &str) -> StdResult<Decimal256> {
+    let price: PriceResponse = querier.query_wasm_smart(oracle, &OracleQueryMsg::Price{
+        base: asset.to_string(),
+        quote: String::from("uusd"),
+    })?;
+    Ok(price.rate)
+}
+
+/// how much more can be borrowed before loan / collateral value reaches target_ltv
+pub fn max_borrow(loan: Uint256, collateral_value: Uint256, target_ltv: Decimal256) -> Uint256 {
+    let target_loan = collateral_value * target_ltv;
+    if target_loan > loan{
+        target_loan - loan
+    } else {
+        Uint256::zero()
+    }
+}
+
+/// how much has to be repaid for loan / collateral value to come back down to safe_ltv
+pub fn repay_to_safe_ltv(loan: Uint256, collateral_value: Uint256, safe_ltv: Decimal256) -> Uint256 {
+    let safe_loan = collateral_value * safe_ltv;
+    if loan > safe_loan{
+        loan - safe_loan
+    } else {
+        Uint256::zero()
+    }
+}
+
+/// uusd paid out per aust when redeeming at block_height
+pub fn query_aust_exchange_rate(querier: &QuerierWrapper, market: &str, block_height: u64) -> StdResult<Decimal256> {
+    let epoch_state: EpochStateResponse = querier.query_wasm_smart(market, &MarketQueryMsg::EpochState{
+        block_height: Some(block_height),
+        distributed_interest: None,
+    })?;
+    Ok(epoch_state.exchange_rate)
+}
+
+/// amount plus the tax terra charges on top when amount of denom is sent
+pub fn amount_with_tax(querier: &QuerierWrapper, denom: &str, amount: Uint128) -> StdResult<Uint128> {
+    let terra_querier = TerraQuerier::new(querier);
+    let tax_rate: Decimal = terra_querier.query_tax_rate()?.rate;
+    let tax_cap: Uint128 = terra_querier.query_tax_cap(denom.to_string())?.cap;
+
+    Ok(amount + min(amount * tax_rate, tax_cap))
+}
+
+/// aust to redeem so the market's payout, after it deducts tax, covers amount uusd
+pub fn aust_to_redeem(querier: &QuerierWrapper, amount: Uint128, exchange_rate: Decimal256) -> StdResult<Uint256> {
+    let gross = Uint256::from(amount_with_tax(querier, "uusd", amount)?);
+
+    //round up, the market rounds the payout down
+    let aust = gross / exchange_rate;
+    if aust * exchange_rate < gross{
+        Ok(aust + Uint256::one())
+    } else {
+        Ok(aust)
+    }
+}
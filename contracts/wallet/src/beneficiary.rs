@@ -0,0 +1,122 @@
+use cosmwasm_std::{from_binary, Addr, BankMsg, CosmosMsg, Empty, Order, Response, StdError, StdResult, Storage, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+use smartwallet::wallet::{BeneficiaryPolicy, PendingBeneficiaryPolicy};
+use crate::state::{BENEFICIARIES, BENEFICIARY_POLICY, PENDING_BENEFICIARY_POLICY, WHITELIST_ACTIVE_AT, whitelisted_contracts};
+use crate::error::ContractError;
+
+pub fn validate_beneficiary_policy(policy: &BeneficiaryPolicy) -> Result<(), ContractError> {
+    //a zero delay would let a new beneficiary be added and paid in one go
+    if policy.delay == 0{
+        return Err(ContractError::InvalidBeneficiaryPolicy{reason: String::from("delay must be non-zero")});
+    }
+    Ok(())
+}
+
+/// policy in force at now, including a pending one whose delay has passed
+pub fn load_beneficiary_policy(storage: &dyn Storage, now: u64) -> StdResult<Option<BeneficiaryPolicy>> {
+    match PENDING_BENEFICIARY_POLICY.may_load(storage)?{
+        Some(pending) if pending.effective_at <= now => Ok(pending.policy),
+        _ => BENEFICIARY_POLICY.may_load(storage),
+    }
+}
+
+/// tightening applies right away; disabling or shortening the delay is pending until the current delay has passed.
+/// returns the pending policy, if any
+pub fn update_beneficiary_policy(storage: &mut dyn Storage, now: u64, policy: Option<BeneficiaryPolicy>) -> StdResult<Option<PendingBeneficiaryPolicy>> {
+    let current = load_beneficiary_policy(storage, now)?;
+
+    let pending = match (&current, &policy){
+        (Some(current), None) => Some(current.delay),
+        (Some(current), Some(policy)) if policy.delay < current.delay => Some(current.delay),
+        _ => None,
+    }.map(|delay| PendingBeneficiaryPolicy{policy: policy.clone(), effective_at: now + delay});
+
+    //the current policy stays in force while the new one is pending
+    let in_force = if pending.is_some(){ current } else { policy };
+    match &in_force{
+        Some(policy) => BENEFICIARY_POLICY.save(storage, policy)?,
+        None => BENEFICIARY_POLICY.remove(storage),
+    }
+    match &pending{
+        Some(pending) => PENDING_BENEFICIARY_POLICY.save(storage, pending)?,
+        None => PENDING_BENEFICIARY_POLICY.remove(storage),
+    }
+
+    Ok(pending)
+}
+
+/// addresses receiving coins or tokens from msg: bank sends, cw20 transfers, sends and allowances,
+/// and any contract called with coins attached. none if msg instantiates a contract with coins,
+/// whose address isn't known up front
+pub fn msg_recipients(msg: &CosmosMsg<Empty>) -> Option<Vec<String>> {
+    match msg{
+        CosmosMsg::Bank(BankMsg::Send{to_address, ..}) => Some(vec![to_address.clone()]),
+        CosmosMsg::Wasm(WasmMsg::Execute{contract_addr, msg, funds}) => {
+            let mut recipients = match from_binary::<Cw20ExecuteMsg>(msg){
+                Ok(Cw20ExecuteMsg::Transfer{recipient, ..}) | Ok(Cw20ExecuteMsg::TransferFrom{recipient, ..}) => vec![recipient],
+                Ok(Cw20ExecuteMsg::Send{contract, ..}) | Ok(Cw20ExecuteMsg::SendFrom{contract, ..}) => vec![contract],
+                Ok(Cw20ExecuteMsg::IncreaseAllowance{spender, ..}) => vec![spender],
+                _ => vec![],
+            };
+            if !funds.is_empty(){
+                recipients.push(contract_addr.clone());
+            }
+            Some(recipients)
+        },
+        CosmosMsg::Wasm(WasmMsg::Instantiate{funds, ..}) if !funds.is_empty() => None,
+        _ => Some(vec![]),
+    }
+}
+
+/// newly whitelisted addresses become recipients once the policy's delay has passed, the same as AddBeneficiary;
+/// addresses no longer whitelisted drop out. called after every whitelist change
+pub fn sync_whitelist_activation(storage: &mut dyn Storage, now: u64) -> StdResult<()> {
+    let active_at = now + load_beneficiary_policy(storage, now)?.map_or(0, |x| x.delay);
+
+    let addresses: Vec<String> = whitelisted_contracts()
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, contract)| contract.address))
+        .collect::<StdResult<Vec<String>>>()?;
+    let activated: Vec<String> = WHITELIST_ACTIVE_AT
+        .keys(storage, None, None, Order::Ascending)
+        .map(|key| String::from_utf8(key).map_err(StdError::from))
+        .collect::<StdResult<Vec<String>>>()?;
+
+    for address in activated.iter().filter(|x| !addresses.contains(x)){
+        WHITELIST_ACTIVE_AT.remove(storage, address.as_str());
+    }
+    for address in addresses.iter(){
+        if !WHITELIST_ACTIVE_AT.has(storage, address.as_str()){
+            WHITELIST_ACTIVE_AT.save(storage, address.as_str(), &active_at)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// while a policy is in force every recipient of msg has to be the wallet itself, an active beneficiary
+/// or a whitelisted contract whose activation has passed
+pub fn assert_beneficiaries(storage: &dyn Storage, now: u64, wallet: &Addr, msg: &CosmosMsg<Empty>) -> Result<(), ContractError> {
+    if load_beneficiary_policy(storage, now)?.is_none(){
+        return Ok(());
+    }
+
+    let recipients = msg_recipients(msg).ok_or(ContractError::FundedInstantiate{})?;
+    for recipient in recipients.into_iter().filter(|x| x != wallet.as_str()){
+        let is_active = |active_at: Option<u64>| active_at.is_some_and(|x| x <= now);
+        if !is_active(BENEFICIARIES.may_load(storage, recipient.as_str())?) && !is_active(WHITELIST_ACTIVE_AT.may_load(storage, recipient.as_str())?){
+            return Err(ContractError::BeneficiaryNotAllowed{address: recipient});
+        }
+    }
+
+    Ok(())
+}
+
+/// assert_beneficiaries on every msg a hot action dispatches
+pub fn assert_response_beneficiaries(storage: &dyn Storage, now: u64, wallet: &Addr, res: &Response) -> Result<(), ContractError> {
+    for sub_msg in res.messages.iter(){
+        assert_beneficiaries(storage, now, wallet, &sub_msg.msg)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use cosmwasm_std::{QuerierWrapper, StdResult, Uint128};
+
+use basset::hub::{QueryMsg as HubQueryMsg, WithdrawableUnbondedResponse, UnbondRequestsResponse, UnbondRequest};
+use crate::error::ContractError;
+
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// validator operator addresses have a *valoper prefix, which addr_validate rejects on terra,
+/// so only their bech32 shape is checked here; the hub refuses validators it doesn't know
+pub fn validate_bond_validators(validators: &[String]) -> Result<(), ContractError> {
+    if validators.is_empty(){
+        return Err(ContractError::InvalidBondValidators{reason: String::from("no validators given")});
+    }
+
+    for (i, validator) in validators.iter().enumerate(){
+        let well_formed = match validator.rsplit_once('1'){
+            Some((prefix, data)) => prefix.ends_with("valoper")
+                && prefix.chars().all(|x| x.is_ascii_lowercase())
+                && data.len() >= 6
+                && data.chars().all(|x| BECH32_CHARSET.contains(x))
+                && validator.len() <= 90,
+            None => false,
+        };
+        if !well_formed{
+            return Err(ContractError::InvalidBondValidators{reason: format!("{} is not a validator address", validator)});
+        }
+        if validators[..i].contains(validator){
+            return Err(ContractError::InvalidBondValidators{reason: format!("duplicate validator {}", validator)});
+        }
+    }
+
+    Ok(())
+}
+
+/// matured uluna the hub holds for address
+pub fn query_withdrawable_unbonded(querier: &QuerierWrapper, hub: &str, address: &str) -> StdResult<Uint128> {
+    let withdrawable: WithdrawableUnbondedResponse = querier.query_wasm_smart(hub, &HubQueryMsg::WithdrawableUnbonded{
+        address: address.to_string(),
+    })?;
+    Ok(withdrawable.withdrawable)
+}
+
+/// pending unbonds of address as (batch id, bluna amount)
+pub fn query_unbond_requests(querier: &QuerierWrapper, hub: &str, address: &str) -> StdResult<UnbondRequest> {
+    let unbond_requests: UnbondRequestsResponse = querier.query_wasm_smart(hub, &HubQueryMsg::UnbondRequests{
+        address: address.to_string(),
+    })?;
+    Ok(unbond_requests.requests)
+}
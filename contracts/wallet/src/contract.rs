@@ -2,67 +2,133 @@
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     to_binary, Binary, Deps, DepsMut, Env,
-    MessageInfo, Response, StdResult, Uint128, Addr, BankMsg, WasmMsg, CosmosMsg, Coin, SubMsg, Reply, StdError, 
+    MessageInfo, Response, StdResult, Storage, Uint128, Addr, BankMsg, WasmMsg, CosmosMsg, Coin, SubMsg, Reply, StdError, Order, Decimal, Empty,
 };
+use cw_storage_plus::{Bound, U64Key};
 
 use smartwallet::wallet::{
-    ExecuteMsg, InstantiateMsg, QueryMsg, ConfigResponse, HotWallet, HotWalletStateResponse, WhitelistedContract, Cw3InstantiateMsg, MultiSigVoter, Duration
+    ExecuteMsg, InstantiateMsg, QueryMsg, ConfigResponse, HotWallet, GasTankStatus, GasTankStatusResponse, WhitelistedContract, Cw3InstantiateMsg, MultiSigVoter, Duration,
+    ActionTemplate, ActionParam, ActionTemplateResponse, ActionTemplatesResponse, SpendingAllowanceResponse, ActionStateResponse,
+    MigrateMsg, HotWalletsResponse, WhitelistedContractsResponse, GuardiansResponse, RecoveryProposal, RecoveryProposalsResponse,
+    FreezeInfo, FreezeResponse, LoanConfig, LoanConfigResponse, BondValidatorsResponse, Airdrop, AirdropsResponse, AssetInfo, SwapPair, SwapPairsResponse, Cw20Balance, Cw20BalancesResponse, WithdrawableUnbondedResponse, UnbondRequestsResponse, TimelockConfig, TimelockedCommand, QueuedCommand, TimelockResponse, QueuedCommandsResponse,
+    BeneficiaryPolicy, BeneficiaryPolicyResponse, Beneficiary, BeneficiariesResponse, JournalEntry, ActorRole, ActionHistoryResponse, ReservesResponse, ContractDrift, WhitelistDriftResponse,
 };
-
-use crate::state::{CONFIG, HOT_WALLETS, Config, HotWalletState};
+use cw2::{set_contract_version, CONTRACT};
+
+use crate::state::{CONFIG, HOT_WALLETS, hot_wallet_configs, whitelisted_contracts, ACTION_TEMPLATES, NEXT_ACTION_TEMPLATE_ID, Config, HotWalletState,
+    GUARDIANS, RECOVERY_PROPOSALS, NEXT_RECOVERY_PROPOSAL_ID, GuardianConfig, FROZEN,
+    LOAN_CONFIG, BOND_VALIDATORS, AIRDROPS, SWAP_PAIRS, PENDING_SWAP, PendingSwap, TRACKED_TOKENS, PENDING_COMPOUND, PendingCompound, TIMELOCK, QUEUED_COMMANDS, NEXT_QUEUED_COMMAND_ID,
+    BENEFICIARIES, PENDING_BENEFICIARY_POLICY, WHITELIST_ACTIVE_AT, journal};
+use crate::action::{FIRST_ACTION_TEMPLATE_ID, validate_template, render_action};
+use crate::spending::{consume_spending_limits, remaining_allowances, validate_spending_limits};
+use crate::gas::{validate_gas_tanks, gas_tank_status};
+use crate::whitelist::{validate_whitelisted_contract, validate_whitelisted_contracts, load_whitelisted_contract, load_whitelisted_address, contract_drift};
+use crate::reserve::{validate_reserves, save_reserves, load_reserves, spendable, assert_reserve, cap_to_reserve, assert_coin_reserves, assert_token_reserve};
+use crate::rate_limit::{consume_action_limit, next_allowed_time, load_action_state, validate_action_limits};
+use crate::migrate::{migrate_hot_wallets_to_map, migrate_to_indexed_maps, migrate_gas_tanks, migrate_reserves, migrate_airdrop_labels};
+use crate::recovery::{validate_guardians, replace_multisig, clear_recovery_proposals};
+use crate::timelock::{validate_timelock, requires_timelock, is_timelocked_config_update, command_funds, command_target, cw20_amount};
+use crate::journal::{actor_role, record_action};
+use crate::beneficiary::{validate_beneficiary_policy, load_beneficiary_policy, update_beneficiary_policy, assert_beneficiaries, assert_response_beneficiaries, sync_whitelist_activation};
+use crate::anchor::{validate_loan_config, query_loan_amount, query_collateral_value, query_price, max_borrow, repay_to_safe_ltv, query_aust_exchange_rate, aust_to_redeem, amount_with_tax};
+use cosmwasm_bignumber::Uint256;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use semver::Version;
 use std::cmp::{min, max};
-use crate::tax_querier::{query_balance, deduct_tax};
-use moneymarket::market::ExecuteMsg::{DepositStable, RepayStable};
+use crate::tax_querier::{query_balance, query_token_balance};
+use basset::deduct_tax;
+use moneymarket::market::ExecuteMsg::{DepositStable, RepayStable, BorrowStable};
+use moneymarket::market::Cw20HookMsg::RedeemStable;
+use moneymarket::overseer::ExecuteMsg::{LockCollateral, UnlockCollateral};
+use moneymarket::custody::ExecuteMsg::WithdrawCollateral;
+use moneymarket::custody::Cw20HookMsg::DepositCollateral;
 use basset::reward::ExecuteMsg::ClaimRewards;
+use basset::reward::{QueryMsg as RewardQueryMsg, AccruedRewardsResponse};
+use basset::hub::ExecuteMsg::{Bond, WithdrawUnbonded};
+use basset::hub::Cw20HookMsg::Unbond;
+use crate::bluna::{query_withdrawable_unbonded, query_unbond_requests, validate_bond_validators};
+use crate::airdrop::validate_airdrop;
+use crate::swap::{validate_swap_pair, asset_denom, query_asset_balance, min_swap_return, Asset, PairExecuteMsg, PairCw20HookMsg};
+use basset::airdrop::{MIRAirdropHandleMsg, PairHandleMsg};
 use crate::error::ContractError;
 use protobuf::Message;
 use crate::response::MsgInstantiateContractResponse;
 
-pub const GAS_BUFFER: u64 = 100000000u64;
+pub const CONTRACT_NAME: &str = "crates.io:smartwallet-wallet";
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const DEFAULT_UUSD_RESERVE: u128 = 100000000u128; //new wallets keep 100 UST until the multisig says otherwise
 pub const ANCHOR_MARKET_CONTRACT: &str = "anchor_market";
 pub const BLUNA_REWARD_CONTRACT: &str = "bluna_reward";
 pub const ANCHOR_EARN_DEPOSIT_ID: u64 = 0u64;
 pub const BLUNA_CLAIM_ID: u64 = 1u64;
 pub const ANCHOR_REPAY_STABLE_ID: u64 = 2u64;
+pub const ANCHOR_BORROW_STABLE_ID: u64 = 3u64;
+pub const ANCHOR_DEPOSIT_COLLATERAL_ID: u64 = 4u64;
+pub const ANCHOR_WITHDRAW_COLLATERAL_ID: u64 = 5u64;
+pub const ANCHOR_REBALANCE_LOAN_ID: u64 = 6u64;
+pub const ANCHOR_EARN_REDEEM_ID: u64 = 7u64;
+pub const BLUNA_BOND_ID: u64 = 8u64;
+pub const BLUNA_UNBOND_ID: u64 = 9u64;
+pub const BLUNA_WITHDRAW_UNBONDED_ID: u64 = 10u64;
+pub const BLUNA_COMPOUND_ID: u64 = 11u64;
+pub const CLAIM_AIRDROP_ID: u64 = 12u64;
+pub const SWAP_ID: u64 = 13u64;
+pub const ANCHOR_OVERSEER_CONTRACT: &str = "anchor_overseer";
+pub const ANCHOR_ORACLE_CONTRACT: &str = "anchor_oracle";
+pub const ANCHOR_CUSTODY_BLUNA_CONTRACT: &str = "anchor_custody_bluna";
+pub const BLUNA_TOKEN_CONTRACT: &str = "bluna_token";
+pub const ANCHOR_AUST_CONTRACT: &str = "anchor_aust";
+pub const BLUNA_HUB_CONTRACT: &str = "bluna_hub";
 
 pub const SPAWN_MULTISIG_REPLY_ID: u64 = 100u64;
+pub const BLUNA_COMPOUND_REPLY_ID: u64 = 101u64;
+pub const SWAP_REPLY_ID: u64 = 102u64;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
 
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    save_reserves(deps.storage, &[Coin{denom: String::from("uusd"), amount: Uint128::from(DEFAULT_UUSD_RESERVE)}])?;
+
     match msg{
-        InstantiateMsg::ExistingMultiSig {hot_wallets, cw3_address, whitelisted_contracts} => instantiate_existing_multisig(deps, hot_wallets, cw3_address, whitelisted_contracts),
-        InstantiateMsg::SpawnMultiSig{hot_wallets, whitelisted_contracts, max_voting_period_in_blocks, required_weight, multisig_voters, cw3_code_id} => instantiate_spawn_multisig(deps, info, hot_wallets, whitelisted_contracts, max_voting_period_in_blocks, required_weight, multisig_voters, cw3_code_id),
+        InstantiateMsg::ExistingMultiSig {hot_wallets, cw3_address, whitelisted_contracts} => instantiate_existing_multisig(deps, env, hot_wallets, cw3_address, whitelisted_contracts),
+        InstantiateMsg::SpawnMultiSig{hot_wallets, whitelisted_contracts, max_voting_period_in_blocks, required_weight, multisig_voters, cw3_code_id} => instantiate_spawn_multisig(deps, env, info, hot_wallets, whitelisted_contracts, max_voting_period_in_blocks, required_weight, multisig_voters, cw3_code_id),
     }
 }
 
 #[allow(clippy::too_many_arguments)]
 pub fn instantiate_existing_multisig(
     deps: DepsMut,
+    env: Env,
     hot_wallets: Vec<HotWallet>,
     cw3_address: String,
     whitelisted_contracts: Vec<WhitelistedContract>,
 ) -> Result<Response, ContractError> {
 
     let config = Config {
-        hot_wallets: hot_wallets,
         cw3_address: deps.api.addr_validate(&cw3_address)?,
-        whitelisted_contracts: whitelisted_contracts,
     };
 
     CONFIG.save(deps.storage, &config)?;
 
-    Ok(Response::new().add_attributes(vec![("action", "init_existing_multisig")]))
+    save_hot_wallets_and_contracts(deps, env, hot_wallets, whitelisted_contracts)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "init_existing_multisig"), ("cw3_address", config.cw3_address.as_str())]))
 }
 
 #[allow(clippy::too_many_arguments)]
 pub fn instantiate_spawn_multisig(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     hot_wallets: Vec<HotWallet>,
     whitelisted_contracts: Vec<WhitelistedContract>,
@@ -73,11 +139,11 @@ pub fn instantiate_spawn_multisig(
 ) -> Result<Response, ContractError> {
 
     CONFIG.save(deps.storage, &Config{
-        hot_wallets: hot_wallets, 
         cw3_address: Addr::unchecked(""), 
-        whitelisted_contracts: whitelisted_contracts,
     })?;
 
+    save_hot_wallets_and_contracts(deps, env, hot_wallets, whitelisted_contracts)?;
+
     Ok(Response::default()
     .add_submessage(SubMsg::reply_on_success(
         CosmosMsg::Wasm(WasmMsg::Instantiate{
@@ -85,7 +151,7 @@ pub fn instantiate_spawn_multisig(
             code_id: cw3_code_id,
             msg: to_binary(&Cw3InstantiateMsg{
                 voters: multisig_voters,
-                required_weight: required_weight,
+                required_weight,
                 max_voting_period: Duration::Height(max_voting_period_in_blocks),
             })?,
             funds: vec![],
@@ -95,8 +161,31 @@ pub fn instantiate_spawn_multisig(
     )))
 }
 
-#[entry_point]
-pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, ContractError> {
+fn save_hot_wallets_and_contracts(
+    deps: DepsMut,
+    env: Env,
+    hot_wallets: Vec<HotWallet>,
+    contracts: Vec<WhitelistedContract>,
+) -> Result<(), ContractError> {
+
+    for hot_wallet in hot_wallets.iter(){
+        validate_gas_tanks(&hot_wallet.gas_tanks)?;
+        validate_spending_limits(&hot_wallet.spending_limits)?;
+        validate_action_limits(&hot_wallet.action_limits)?;
+        hot_wallet_configs().save(deps.storage, hot_wallet.address.as_str(), hot_wallet)?;
+    }
+
+    validate_whitelisted_contracts(deps.as_ref(), &contracts)?;
+    for contract in contracts.iter(){
+        whitelisted_contracts().save(deps.storage, contract.label.as_str(), contract)?;
+    }
+    sync_whitelist_activation(deps.storage, env.block.time.seconds())?;
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
     match reply.id{
         SPAWN_MULTISIG_REPLY_ID => {
 
@@ -111,37 +200,188 @@ pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, Contrac
 
             Ok(Response::new())
         },
+        BLUNA_COMPOUND_REPLY_ID => reply_bluna_compound(deps, env),
+        SWAP_REPLY_ID => reply_swap(deps, env),
         _ => Err(ContractError::InvalidReplyId{})
     }
 }
 
 
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+
+    //0.2.0 was deployed without a cw2 record
+    //only a missing record falls back to it; unreadable cw2 data is an error
+    let stored_version = match CONTRACT.may_load(deps.storage)?{
+        Some(contract_version) => {
+            if contract_version.contract != CONTRACT_NAME{
+                return Err(ContractError::MigrationWrongContract{stored: contract_version.contract});
+            }
+            contract_version.version
+        },
+        None => String::from("0.2.0"),
+    };
+
+    let stored = Version::parse(&stored_version).map_err(|_| StdError::generic_err("invalid stored contract version"))?;
+    let current = Version::parse(CONTRACT_VERSION).map_err(|_| StdError::generic_err("invalid contract version"))?;
+
+    if stored > current{
+        return Err(ContractError::MigrationDowngrade{stored: stored_version, current: String::from(CONTRACT_VERSION)});
+    }
+
+    //state upgrades, oldest first
+    if stored < Version::new(0, 3, 0){
+        migrate_hot_wallets_to_map(deps.storage)?;
+    }
+    if stored < Version::new(0, 4, 0){
+        migrate_to_indexed_maps(deps.storage)?;
+    }
+    if stored < Version::new(0, 5, 0){
+        migrate_gas_tanks(deps.storage)?;
+    }
+    if stored < Version::new(0, 6, 0){
+        migrate_reserves(deps.storage)?;
+        migrate_airdrop_labels(deps.storage)?;
+        //no beneficiary policy yet, so the current whitelist is active right away
+        sync_whitelist_activation(deps.storage, env.block.time.seconds())?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "migrate"), ("from_version", stored_version.as_str()), ("to_version", CONTRACT_VERSION)]))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+
+    //looked up first; the msg itself may remove the hot wallet or replace the multisig.
+    //the permissionless recovery and queue executions are journaled whoever sends them
+    let role = match (actor_role(deps.storage, &info.sender)?, &msg){
+        (None, ExecuteMsg::ExecuteRecovery{..}) | (None, ExecuteMsg::ExecuteQueued{..}) => Some(ActorRole::Anyone),
+        (role, _) => role,
+    };
+
+    let res = execute_msg(deps.branch(), env.clone(), info.clone(), msg)?.add_attribute("actor", info.sender.as_str());
+
+    //whatever a hot action sends goes to the wallet, an active beneficiary or a whitelisted contract past the
+    //policy's delay. UpsertHot and whitelist changes take effect right away, so the delay is what holds them back
+    if role == Some(ActorRole::HotWallet){
+        assert_response_beneficiaries(deps.storage, env.block.time.seconds(), &env.contract.address, &res)?;
+    }
+
+    if let Some(role) = role{
+        let id = record_action(deps.storage, &env, &info.sender, role, &res)?;
+        return Ok(res.add_attribute("journal_id", id.to_string()));
+    }
+
+    Ok(res)
+}
+
+fn execute_msg(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+
+    //the multisig's config updates wait out the timelock like Execute; anyone else gets Unauthorized from the handler
+    if is_timelocked_config_update(&msg) && info.sender == CONFIG.load(deps.storage)?.cw3_address{
+        let command = TimelockedCommand::UpdateConfig{msg: Box::new(msg)};
+        if let Some(timelock) = TIMELOCK.may_load(deps.storage)?.filter(|x| requires_timelock(Some(x), &command)){
+            return queue_command(deps.storage, env, &timelock, command);
+        }
+        return run_command(deps, env, command);
+    }
+
     match msg {
 
-        //hot wallet actions
-        ExecuteMsg::AnchorEarnDeposit {amount} => execute_anchor_earn_deposit(deps, info, amount), //id=0
-        ExecuteMsg::BlunaClaim{} => execute_bluna_claim_rewards(deps, info), //id=1
-        ExecuteMsg::RepayStable{amount} => execute_repay_stable(deps, info, amount), //id=2
+        //hot wallet actions. the hardwired ids stay out of the action registry's scope: each works out
+        //its amount on chain, so new automations go through RunAction and these are kept as they are
+        ExecuteMsg::AnchorEarnDeposit {amount} => execute_anchor_earn_deposit(deps, env, info, amount), //id=0
+        ExecuteMsg::BlunaClaim{} => execute_bluna_claim_rewards(deps, env, info), //id=1
+        ExecuteMsg::RepayStable{amount} => execute_repay_stable(deps, env, info, amount), //id=2
+        ExecuteMsg::BorrowStable{amount} => execute_borrow_stable(deps, env, info, amount), //id=3
+        ExecuteMsg::DepositCollateral{amount} => execute_deposit_collateral(deps, env, info, amount), //id=4
+        ExecuteMsg::WithdrawCollateral{amount} => execute_withdraw_collateral(deps, env, info, amount), //id=5
+        ExecuteMsg::RebalanceLoan{} => execute_rebalance_loan(deps, env, info), //id=6
+        ExecuteMsg::AnchorEarnRedeem{amount} => execute_anchor_earn_redeem(deps, env, info, amount), //id=7
+        ExecuteMsg::BlunaBond{amount, validator} => execute_bluna_bond(deps, env, info, amount, validator), //id=8
+        ExecuteMsg::BlunaUnbond{amount} => execute_bluna_unbond(deps, env, info, amount), //id=9
+        ExecuteMsg::BlunaWithdrawUnbonded{} => execute_bluna_withdraw_unbonded(deps, env, info), //id=10
+        ExecuteMsg::BlunaCompound{} => execute_bluna_compound(deps, env, info), //id=11
+        ExecuteMsg::ClaimAirdrop{label, stage, amount, proof, swap, belief_price} => execute_claim_airdrop(deps, env, info, label, stage, amount, proof, swap, belief_price), //id=12
+        ExecuteMsg::Swap{pair, offer_amount, belief_price, max_spread, min_receive} => execute_swap(deps, env, info, pair, offer_amount, belief_price, max_spread, min_receive), //id=13
         ExecuteMsg::FillUpGas{} => execute_fill_up_gas(deps, env, info), //any
+        ExecuteMsg::RunAction{id, params} => execute_run_action(deps, env, info, id, params), //id=template id
 
         //hot wallet mgmt
         ExecuteMsg::RemoveHot {address} => execute_remove_hot(deps, info, address),
         ExecuteMsg::UpsertHot {hot_wallet} => execute_upsert_hot(deps, info, hot_wallet),
-        ExecuteMsg::ReplaceContractWhitelist { whitelisted_contracts } => execute_replace_contracts(deps, info, whitelisted_contracts),
+        ExecuteMsg::ReplaceContractWhitelist { whitelisted_contracts } => execute_replace_contracts(deps, env, info, whitelisted_contracts),
+        ExecuteMsg::AddWhitelistedContract {contract} => execute_add_whitelisted_contract(deps, env, info, contract),
+        ExecuteMsg::UpdateWhitelistedContract {contract} => execute_update_whitelisted_contract(deps, env, info, contract),
+        ExecuteMsg::RemoveWhitelistedContract {label} => execute_remove_whitelisted_contract(deps, env, info, label),
+
+        //anchor borrow mgmt
+        ExecuteMsg::UpdateLoanConfig {loan_config} => execute_update_loan_config(deps, info, loan_config),
+
+        //bluna bonding mgmt
+        ExecuteMsg::UpdateBondValidators {validators} => execute_update_bond_validators(deps, info, validators),
+
+        //airdrop mgmt
+        ExecuteMsg::UpsertAirdrop {airdrop} => execute_upsert_airdrop(deps, info, airdrop),
+        ExecuteMsg::RemoveAirdrop {label} => execute_remove_airdrop(deps, info, label),
+
+        //swap pair mgmt
+        ExecuteMsg::UpsertSwapPair {swap_pair} => execute_upsert_swap_pair(deps, info, swap_pair),
+        ExecuteMsg::RemoveSwapPair {label} => execute_remove_swap_pair(deps, info, label),
+
+        //action template registry mgmt
+        ExecuteMsg::AddActionTemplate {template} => execute_add_action_template(deps, info, template),
+        ExecuteMsg::UpdateActionTemplate {id, template} => execute_update_action_template(deps, info, id, template),
+        ExecuteMsg::RemoveActionTemplate {id} => execute_remove_action_template(deps, info, id),
 
         //update multsig
-        ExecuteMsg::ReplaceMultisig {address} => execute_replace_multisig(deps, info, address),
+        ExecuteMsg::ReplaceMultisig {address} => execute_replace_multisig(deps, env, info, address),
+
+        //emergency stop
+        ExecuteMsg::Freeze {reason} => execute_freeze(deps, env, info, reason),
+        ExecuteMsg::Unfreeze {} => execute_unfreeze(deps, info),
+
+        //guardian recovery of the multisig
+        ExecuteMsg::UpdateGuardians {guardians, threshold, delay} => execute_update_guardians(deps, info, guardians, threshold, delay),
+        ExecuteMsg::ProposeRecovery {new_cw3_address} => execute_propose_recovery(deps, env, info, new_cw3_address),
+        ExecuteMsg::ApproveRecovery {id} => execute_approve_recovery(deps, env, info, id),
+        ExecuteMsg::VetoRecovery {id} => execute_veto_recovery(deps, info, id),
+        ExecuteMsg::ExecuteRecovery {id} => execute_recovery(deps, env, id),
 
         //generalized exec for multisig
-        ExecuteMsg::Execute {command} => execute_command(deps, info, command),
+        ExecuteMsg::Execute {command} => execute_command(deps, env, info, command),
+
+        //cw20 custody
+        ExecuteMsg::Receive(cw20_msg) => execute_receive_cw20(deps, info, cw20_msg),
+        ExecuteMsg::AddTrackedToken {token} => execute_add_tracked_token(deps, env, info, token),
+        ExecuteMsg::RemoveTrackedToken {token} => execute_remove_tracked_token(deps, info, token),
+        ExecuteMsg::WithdrawCw20 {token, amount, recipient} => execute_withdraw_cw20(deps, env, info, token, amount, recipient),
+
+        //timelock on multisig commands
+        ExecuteMsg::UpdateTimelock {timelock} => execute_update_timelock(deps, env, info, timelock),
+        ExecuteMsg::CancelQueued {id} => execute_cancel_queued(deps, info, id),
+        ExecuteMsg::ExecuteQueued {id} => execute_queued(deps, env, id),
+
+        //beneficiary allowlist
+        ExecuteMsg::UpdateBeneficiaryPolicy {policy} => execute_update_beneficiary_policy(deps, env, info, policy),
+        ExecuteMsg::AddBeneficiary {address} => execute_add_beneficiary(deps, env, info, address),
+        ExecuteMsg::RemoveBeneficiary {address} => execute_remove_beneficiary(deps, info, address),
+
+        //reserves
+        ExecuteMsg::UpdateReserves {reserves} => execute_update_reserves(deps, info, reserves),
     }
 }
 
@@ -149,335 +389,2458 @@ pub fn execute(
 #[allow(clippy::too_many_arguments)]
 pub fn execute_repay_stable(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
 
-    let config: Config = CONFIG.load(deps.storage)?;
-
-    let hot_wallet_config = config.hot_wallets.iter().find(|&x| x.address == info.sender.to_string());
+    //freeze check
+    assert_not_frozen(deps.storage)?;
 
     //hot wallet check
-    if hot_wallet_config.is_none(){
-        return Err(ContractError::Unauthorized{});
-    }
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
 
     //hot wallet is enabled for this action
-    if hot_wallet_config.unwrap().whitelisted_messages.iter().find(|&&x| x == ANCHOR_REPAY_STABLE_ID).is_none(){
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == ANCHOR_REPAY_STABLE_ID).is_none(){
         return Err(ContractError::UnauthorizedAction{});
     }
 
-    let anchor_market_contract = config.whitelisted_contracts.iter().find(|&x| x.label == String::from(ANCHOR_MARKET_CONTRACT));
+    consume_action_limit(deps.storage, &hot_wallet_config, ANCHOR_REPAY_STABLE_ID, env.block.time.seconds())?;
 
     //contract check
-    if anchor_market_contract.is_none(){
-        return Err(ContractError::ContractNotWhitelisted{});
-    }
+    let anchor_market_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_MARKET_CONTRACT)?;
+
+    let funds = vec![Coin{
+        denom: String::from("uusd"),
+        amount,
+    }];
+
+    let smart_wallet_balance = query_balance(deps.as_ref(), env.contract.address.to_string(), String::from("uusd"))?;
+    assert_reserve(deps.storage, "uusd", smart_wallet_balance, amount)?;
+
+    consume_spending_limits(deps.storage, &hot_wallet_config, &funds, env.block.time.seconds())?;
+
+    //the send is taxed on top
+    let tax = amount_with_tax(&deps.querier, "uusd", amount)? - amount;
 
     let repay_msg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: anchor_market_contract.unwrap().address.clone(),
-        funds: vec![Coin{
-            denom: String::from("uusd"),
-            amount: amount,
-        }],
+        contract_addr: anchor_market_contract.address.clone(),
+        funds,
         msg: to_binary(&RepayStable{})?,
     });
 
-    Ok(Response::new().add_attributes(vec![("action", "anchor_earn_deposit")]).add_message(repay_msg))
+    Ok(Response::new().add_attributes(vec![
+        ("action", "repay_stable"),
+        ("action_id", ANCHOR_REPAY_STABLE_ID.to_string().as_str()),
+        ("contract", anchor_market_contract.address.as_str()),
+        ("denom", "uusd"),
+        ("amount", amount.to_string().as_str()),
+        ("tax", tax.to_string().as_str()),
+        ("balance", smart_wallet_balance.saturating_sub(amount).to_string().as_str()),
+    ]).add_message(repay_msg))
 }
 
 #[allow(clippy::too_many_arguments)]
 pub fn execute_anchor_earn_deposit(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
 
-    let config: Config = CONFIG.load(deps.storage)?;
-
-    let hot_wallet_config = config.hot_wallets.iter().find(|&x| x.address == info.sender.to_string());
+    //freeze check
+    assert_not_frozen(deps.storage)?;
 
     //hot wallet check
-    if hot_wallet_config.is_none(){
-        return Err(ContractError::Unauthorized{});
-    }
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
 
     //hot wallet is enabled for this action
-    if hot_wallet_config.unwrap().whitelisted_messages.iter().find(|&&x| x == ANCHOR_EARN_DEPOSIT_ID).is_none(){
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == ANCHOR_EARN_DEPOSIT_ID).is_none(){
         return Err(ContractError::UnauthorizedAction{});
     }
 
-    let anchor_market_contract = config.whitelisted_contracts.iter().find(|&x| x.label == String::from(ANCHOR_MARKET_CONTRACT));
+    consume_action_limit(deps.storage, &hot_wallet_config, ANCHOR_EARN_DEPOSIT_ID, env.block.time.seconds())?;
 
     //contract check
-    if anchor_market_contract.is_none(){
-        return Err(ContractError::ContractNotWhitelisted{});
-    }
+    let anchor_market_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_MARKET_CONTRACT)?;
 
-    if query_balance(deps.as_ref(), info.sender.to_string(), String::from("uusd")).unwrap() < Uint128::from(GAS_BUFFER){
-        return Err(ContractError::SmartWalletGas{});
-    }
+    //figure out send amount, net reserve
+    let smart_wallet_balance = query_balance(deps.as_ref(), env.contract.address.to_string(), String::from("uusd"))?;
+    let deposit_amount = cap_to_reserve(deps.storage, "uusd", smart_wallet_balance, amount)?;
+
+    let funds = vec![Coin{
+        denom: String::from("uusd"),
+        amount: deposit_amount,
+    }];
 
-    //figure out send amount, net gas buffer
-    let gas_adjusted_amount = min(
-        query_balance(deps.as_ref(), info.sender.to_string(), String::from("uusd")).unwrap() - Uint128::from(GAS_BUFFER),
-        amount.into());
+    consume_spending_limits(deps.storage, &hot_wallet_config, &funds, env.block.time.seconds())?;
+
+    //the send is taxed on top
+    let tax = amount_with_tax(&deps.querier, "uusd", deposit_amount)? - deposit_amount;
 
     let earn_msg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: anchor_market_contract.unwrap().address.clone(),
-        funds: vec![Coin{
-            denom: String::from("uusd"),
-            amount: gas_adjusted_amount,
-        }],
+        contract_addr: anchor_market_contract.address.clone(),
+        funds,
         msg: to_binary(&DepositStable{})?,
     });
 
-    Ok(Response::new().add_attributes(vec![("action", "anchor_earn_deposit")]).add_message(earn_msg))
+    Ok(Response::new().add_attributes(vec![
+        ("action", "anchor_earn_deposit"),
+        ("action_id", ANCHOR_EARN_DEPOSIT_ID.to_string().as_str()),
+        ("contract", anchor_market_contract.address.as_str()),
+        ("denom", "uusd"),
+        ("amount", deposit_amount.to_string().as_str()),
+        ("tax", tax.to_string().as_str()),
+        ("balance", (smart_wallet_balance - deposit_amount).to_string().as_str()),
+    ]).add_message(earn_msg))
 }
 
 #[allow(clippy::too_many_arguments)]
-pub fn execute_bluna_claim_rewards(
+pub fn execute_anchor_earn_redeem(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
+    amount: Uint128,
 ) -> Result<Response, ContractError> {
 
-    let config: Config = CONFIG.load(deps.storage)?;
-
-    let hot_wallet_config = config.hot_wallets.iter().find(|&x| x.address == info.sender.to_string());
+    //freeze check
+    assert_not_frozen(deps.storage)?;
 
     //hot wallet check
-    if hot_wallet_config.is_none(){
-        return Err(ContractError::Unauthorized{});
-    }
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
 
     //hot wallet is enabled for this action
-    if hot_wallet_config.unwrap().whitelisted_messages.iter().find(|&&x| x == BLUNA_CLAIM_ID).is_none(){
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == ANCHOR_EARN_REDEEM_ID).is_none(){
         return Err(ContractError::UnauthorizedAction{});
     }
 
-    let bluna_reward_contract = config.whitelisted_contracts.iter().find(|&x| x.label == String::from(BLUNA_REWARD_CONTRACT));
+    consume_action_limit(deps.storage, &hot_wallet_config, ANCHOR_EARN_REDEEM_ID, env.block.time.seconds())?;
 
     //contract check
-    if bluna_reward_contract.is_none(){
-        return Err(ContractError::ContractNotWhitelisted{});
+    let anchor_market_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_MARKET_CONTRACT)?;
+    let anchor_aust_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_AUST_CONTRACT)?;
+
+    let aust_balance = query_token_balance(deps.as_ref(), anchor_aust_contract.address.clone(), env.contract.address.to_string())?;
+    let redeem_amount = cap_to_reserve(deps.storage, &anchor_aust_contract.address, aust_balance, amount)?;
+
+    //spending limits on the aust token address cap redemptions
+    consume_spending_limits(deps.storage, &hot_wallet_config, &[Coin{denom: anchor_aust_contract.address.clone(), amount: redeem_amount}], env.block.time.seconds())?;
+
+    //the market pays out to the cw20 sender, i.e. the smart wallet
+    let redeem_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: anchor_aust_contract.address.clone(),
+        funds: vec![],
+        msg: to_binary(&Cw20ExecuteMsg::Send{
+            contract: anchor_market_contract.address.clone(),
+            amount: redeem_amount,
+            msg: to_binary(&RedeemStable{})?,
+        })?,
+    });
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "anchor_earn_redeem"),
+        ("action_id", ANCHOR_EARN_REDEEM_ID.to_string().as_str()),
+        ("contract", anchor_market_contract.address.as_str()),
+        ("denom", anchor_aust_contract.address.as_str()),
+        ("amount", redeem_amount.to_string().as_str()),
+        ("balance", (aust_balance - redeem_amount).to_string().as_str()),
+    ]).add_message(redeem_msg))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_bluna_claim_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+
+    //freeze check
+    assert_not_frozen(deps.storage)?;
+
+    //hot wallet check
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
+
+    //hot wallet is enabled for this action
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == BLUNA_CLAIM_ID).is_none(){
+        return Err(ContractError::UnauthorizedAction{});
     }
 
+    consume_action_limit(deps.storage, &hot_wallet_config, BLUNA_CLAIM_ID, env.block.time.seconds())?;
+
+    //contract check
+    let bluna_reward_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), BLUNA_REWARD_CONTRACT)?;
+
     let claim_msg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: bluna_reward_contract.unwrap().address.clone(),
+        contract_addr: bluna_reward_contract.address.clone(),
         funds: vec![],
         msg: to_binary(&ClaimRewards{recipient: None})?,
     });
 
-
-    Ok(Response::new().add_attributes(vec![("action", "bluna_claim_rewards")]).add_message(claim_msg))
+    Ok(Response::new().add_attributes(vec![
+        ("action", "bluna_claim_rewards"),
+        ("action_id", BLUNA_CLAIM_ID.to_string().as_str()),
+        ("contract", bluna_reward_contract.address.as_str()),
+    ]).add_message(claim_msg))
 }
 
 #[allow(clippy::too_many_arguments)]
-pub fn execute_fill_up_gas(
+pub fn execute_bluna_bond(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    amount: Uint128,
+    validator: String,
 ) -> Result<Response, ContractError> {
 
-    let config: Config = CONFIG.load(deps.storage)?;
-
-    let hot_wallet_config = config.hot_wallets.iter().find(|&x| x.address == info.sender.to_string());
+    //freeze check
+    assert_not_frozen(deps.storage)?;
 
     //hot wallet check
-    if hot_wallet_config.is_none(){
-        return Err(ContractError::Unauthorized{});
-    }
-
-    let mut hot_wallet_state = HOT_WALLETS
-        .may_load(deps.storage, info.sender.to_string())?
-        .unwrap_or(
-            HotWalletState{
-                address: info.sender.to_string(), 
-                last_gas_fillup: env.block.time.seconds() - hot_wallet_config.unwrap().gas_cooldown - 10u64,
-        });
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
 
-    //cooldown check
-    if hot_wallet_state.last_gas_fillup + hot_wallet_config.unwrap().gas_cooldown > env.block.time.seconds(){
-        return Err(ContractError::GasCooldown{});
+    //hot wallet is enabled for this action
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == BLUNA_BOND_ID).is_none(){
+        return Err(ContractError::UnauthorizedAction{});
     }
 
-    //figure out how much gas needed to fill hot wallet's tank
-    let hot_wallet_gas_level = query_balance(deps.as_ref(), info.sender.to_string(), String::from("uusd")).unwrap();
+    consume_action_limit(deps.storage, &hot_wallet_config, BLUNA_BOND_ID, env.block.time.seconds())?;
 
-    if hot_wallet_config.unwrap().gas_tank_max <= hot_wallet_gas_level {
-        return Err(ContractError::GasTankFull{});
+    //validator check
+    if !BOND_VALIDATORS.may_load(deps.storage)?.unwrap_or_default().contains(&validator){
+        return Err(ContractError::ValidatorNotApproved{});
     }
 
-    let hot_wallet_gas_need = hot_wallet_config.unwrap().gas_tank_max - hot_wallet_gas_level;
+    //contract check
+    let bluna_hub_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), BLUNA_HUB_CONTRACT)?;
 
-    //sufficient smart_wallet uusd check
-    if query_balance(deps.as_ref(), env.contract.address.to_string(), String::from("uusd")).unwrap() < hot_wallet_gas_need + Uint128::from(GAS_BUFFER) {
-        return Err(ContractError::SmartWalletGas{});
-    }
+    let smart_wallet_balance = query_balance(deps.as_ref(), env.contract.address.to_string(), String::from("uluna"))?;
 
-    let bank_msg = CosmosMsg::Bank(BankMsg::Send{
-        to_address: info.sender.to_string(),
-        amount: vec![deduct_tax(
-            &deps.querier,
-            Coin{
-                denom: String::from("uusd"),
-                amount: hot_wallet_gas_need,
-            }
-        )?]
-    });
+    let bond_amount = cap_to_reserve(deps.storage, "uluna", smart_wallet_balance, amount)?;
+    let funds = vec![Coin{
+        denom: String::from("uluna"),
+        amount: bond_amount,
+    }];
 
-    hot_wallet_state.last_gas_fillup = env.block.time.seconds();
+    consume_spending_limits(deps.storage, &hot_wallet_config, &funds, env.block.time.seconds())?;
 
-    HOT_WALLETS.save(deps.storage, info.sender.to_string(), &hot_wallet_state)?;
+    //the send is taxed on top
+    let tax = amount_with_tax(&deps.querier, "uluna", bond_amount)? - bond_amount;
+
+    let bond_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: bluna_hub_contract.address.clone(),
+        funds,
+        msg: to_binary(&Bond{validator: validator.clone()})?,
+    });
 
-    Ok(Response::new().add_attributes(vec![("action", "fill_up_gas")]).add_message(bank_msg))
+    Ok(Response::new().add_attributes(vec![
+        ("action", "bluna_bond"),
+        ("action_id", BLUNA_BOND_ID.to_string().as_str()),
+        ("contract", bluna_hub_contract.address.as_str()),
+        ("validator", validator.as_str()),
+        ("denom", "uluna"),
+        ("amount", bond_amount.to_string().as_str()),
+        ("tax", tax.to_string().as_str()),
+        ("balance", (smart_wallet_balance - bond_amount).to_string().as_str()),
+    ]).add_message(bond_msg))
 }
 
 #[allow(clippy::too_many_arguments)]
-pub fn execute_remove_hot(
+pub fn execute_bluna_unbond(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    address: String,
+    amount: Uint128,
 ) -> Result<Response, ContractError> {
 
-    let mut config: Config = CONFIG.load(deps.storage)?;
+    //freeze check
+    assert_not_frozen(deps.storage)?;
 
-    //multisig check
-    if info.sender.to_string() != config.cw3_address{
-        return Err(ContractError::Unauthorized{});
+    //hot wallet check
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
+
+    //hot wallet is enabled for this action
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == BLUNA_UNBOND_ID).is_none(){
+        return Err(ContractError::UnauthorizedAction{});
     }
 
-    let hot_wallet_config = config.hot_wallets.iter().find(|&x| x.address == address);
+    consume_action_limit(deps.storage, &hot_wallet_config, BLUNA_UNBOND_ID, env.block.time.seconds())?;
 
-    //check if valid hot address
-    if hot_wallet_config.is_none(){
-        return Err(ContractError::InvalidHotAddress{});
-    }
+    //contract check
+    let bluna_hub_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), BLUNA_HUB_CONTRACT)?;
+    let bluna_token_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), BLUNA_TOKEN_CONTRACT)?;
 
-    //remove from state
-    HOT_WALLETS.remove(deps.storage, address.clone());
+    let bluna_balance = query_token_balance(deps.as_ref(), bluna_token_contract.address.clone(), env.contract.address.to_string())?;
+    let unbond_amount = cap_to_reserve(deps.storage, &bluna_token_contract.address, bluna_balance, amount)?;
 
-    //remove from config
-    config.hot_wallets.retain(|x| x.address != address);
+    //spending limits on the bluna token address cap unbonding
+    consume_spending_limits(deps.storage, &hot_wallet_config, &[Coin{denom: bluna_token_contract.address.clone(), amount: unbond_amount}], env.block.time.seconds())?;
 
-    CONFIG.save(deps.storage, &config)?;
+    let unbond_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: bluna_token_contract.address.clone(),
+        funds: vec![],
+        msg: to_binary(&Cw20ExecuteMsg::Send{
+            contract: bluna_hub_contract.address.clone(),
+            amount: unbond_amount,
+            msg: to_binary(&Unbond{})?,
+        })?,
+    });
 
-    Ok(Response::new().add_attributes(vec![("action", "remove_hot")]))
+    Ok(Response::new().add_attributes(vec![
+        ("action", "bluna_unbond"),
+        ("action_id", BLUNA_UNBOND_ID.to_string().as_str()),
+        ("contract", bluna_hub_contract.address.as_str()),
+        ("denom", bluna_token_contract.address.as_str()),
+        ("amount", unbond_amount.to_string().as_str()),
+        ("balance", (bluna_balance - unbond_amount).to_string().as_str()),
+    ]).add_message(unbond_msg))
 }
 
 #[allow(clippy::too_many_arguments)]
-pub fn execute_upsert_hot(
+pub fn execute_bluna_withdraw_unbonded(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    hot_wallet: HotWallet,
 ) -> Result<Response, ContractError> {
 
-    let mut config: Config = CONFIG.load(deps.storage)?;
+    //freeze check
+    assert_not_frozen(deps.storage)?;
 
-    //multisig check
-    if info.sender.to_string() != config.cw3_address{
-        return Err(ContractError::Unauthorized{});
+    //hot wallet check
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
+
+    //hot wallet is enabled for this action
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == BLUNA_WITHDRAW_UNBONDED_ID).is_none(){
+        return Err(ContractError::UnauthorizedAction{});
     }
 
-    //check if valid hot address
-    let address: Addr = deps.api.addr_validate(&hot_wallet.address)?;
+    consume_action_limit(deps.storage, &hot_wallet_config, BLUNA_WITHDRAW_UNBONDED_ID, env.block.time.seconds())?;
 
-    //remove from config
-    config.hot_wallets.retain(|x| x.address != address);
-    config.hot_wallets.push(hot_wallet);
+    //contract check
+    let bluna_hub_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), BLUNA_HUB_CONTRACT)?;
 
-    CONFIG.save(deps.storage, &config)?;
+    //the hub pays out to the sender, i.e. the smart wallet
+    let withdraw_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: bluna_hub_contract.address.clone(),
+        funds: vec![],
+        msg: to_binary(&WithdrawUnbonded{})?,
+    });
 
-    Ok(Response::new().add_attributes(vec![("action", "upsert_hot")]))
+    Ok(Response::new().add_attributes(vec![
+        ("action", "bluna_withdraw_unbonded"),
+        ("action_id", BLUNA_WITHDRAW_UNBONDED_ID.to_string().as_str()),
+        ("contract", bluna_hub_contract.address.as_str()),
+    ]).add_message(withdraw_msg))
 }
 
 #[allow(clippy::too_many_arguments)]
-pub fn execute_replace_contracts(
+pub fn execute_bluna_compound(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    whitelisted_contracts: Vec<WhitelistedContract>,
 ) -> Result<Response, ContractError> {
 
-    let mut config: Config = CONFIG.load(deps.storage)?;
+    //freeze check
+    assert_not_frozen(deps.storage)?;
 
-    //multisig check
-    if info.sender.to_string() != config.cw3_address{
-        return Err(ContractError::Unauthorized{});
+    //hot wallet check
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
+
+    //hot wallet is enabled for this action
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == BLUNA_COMPOUND_ID).is_none(){
+        return Err(ContractError::UnauthorizedAction{});
     }
 
-    config.whitelisted_contracts = whitelisted_contracts;
+    consume_action_limit(deps.storage, &hot_wallet_config, BLUNA_COMPOUND_ID, env.block.time.seconds())?;
 
-    CONFIG.save(deps.storage, &config)?;
+    //contract check
+    let bluna_reward_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), BLUNA_REWARD_CONTRACT)?;
+    load_whitelisted_contract(deps.as_ref(), ANCHOR_MARKET_CONTRACT)?;
+
+    let accrued: AccruedRewardsResponse = deps.querier.query_wasm_smart(&bluna_reward_contract.address, &RewardQueryMsg::AccruedRewards{
+        address: env.contract.address.to_string(),
+    })?;
+    if accrued.rewards.is_zero(){
+        return Err(ContractError::NothingToCompound{});
+    }
+
+    //the reply deposits whatever the claim actually paid out
+    let balance_before = query_balance(deps.as_ref(), env.contract.address.to_string(), String::from("uusd"))?;
+    PENDING_COMPOUND.save(deps.storage, &PendingCompound{
+        hot_wallet: hot_wallet_config.address,
+        balance_before,
+    })?;
+
+    let claim_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: bluna_reward_contract.address.clone(),
+        funds: vec![],
+        msg: to_binary(&ClaimRewards{recipient: None})?,
+    });
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "bluna_compound"),
+            ("action_id", BLUNA_COMPOUND_ID.to_string().as_str()),
+            ("contract", bluna_reward_contract.address.as_str()),
+            ("accrued_rewards", accrued.rewards.to_string().as_str()),
+        ])
+        .add_submessage(SubMsg::reply_on_success(claim_msg, BLUNA_COMPOUND_REPLY_ID)))
+}
+
+fn reply_bluna_compound(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+
+    let pending: PendingCompound = PENDING_COMPOUND.load(deps.storage)?;
+    PENDING_COMPOUND.remove(deps.storage);
 
-    Ok(Response::new().add_attributes(vec![("action", "replace_contracts")]))
+    let hot_wallet_config: HotWallet = hot_wallet_configs().load(deps.storage, pending.hot_wallet.as_str())?;
+    let anchor_market_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_MARKET_CONTRACT)?;
+
+    let smart_wallet_balance = query_balance(deps.as_ref(), env.contract.address.to_string(), String::from("uusd"))?;
+    let received = smart_wallet_balance.checked_sub(pending.balance_before).unwrap_or_default();
+
+    //the reserve stays in the smart wallet, and the market only gets what is left after tax
+    let gross = min(received, spendable(deps.storage, "uusd", smart_wallet_balance)?);
+    let deposit = deduct_tax(&deps.querier, Coin{
+        denom: String::from("uusd"),
+        amount: gross,
+    })?;
+
+    let mut res = Response::new().add_attributes(vec![
+        ("action", "bluna_compound_deposit"),
+        ("action_id", BLUNA_COMPOUND_ID.to_string().as_str()),
+        ("contract", anchor_market_contract.address.as_str()),
+        ("denom", "uusd"),
+        ("received", received.to_string().as_str()),
+        ("amount", deposit.amount.to_string().as_str()),
+        ("tax", (gross - deposit.amount).to_string().as_str()),
+        ("balance", (smart_wallet_balance - gross).to_string().as_str()),
+    ]);
+
+    if !deposit.amount.is_zero(){
+        let funds = vec![deposit];
+        consume_spending_limits(deps.storage, &hot_wallet_config, &funds, env.block.time.seconds())?;
+
+        res = res.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: anchor_market_contract.address,
+            funds,
+            msg: to_binary(&DepositStable{})?,
+        }));
+    }
+
+    record_reply(deps.storage, &env, &pending.hot_wallet, res)
 }
 
 #[allow(clippy::too_many_arguments)]
-pub fn execute_replace_multisig(
+pub fn execute_claim_airdrop(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    address: String,
+    label: String,
+    stage: u8,
+    amount: Uint128,
+    proof: Vec<String>,
+    swap: bool,
+    belief_price: Option<Decimal>,
 ) -> Result<Response, ContractError> {
 
-    let mut config: Config = CONFIG.load(deps.storage)?;
+    //freeze check
+    assert_not_frozen(deps.storage)?;
 
-    //multisig check
-    if info.sender.to_string() != config.cw3_address{
-        return Err(ContractError::Unauthorized{});
+    //hot wallet check
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
+
+    //hot wallet is enabled for this action
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == CLAIM_AIRDROP_ID).is_none(){
+        return Err(ContractError::UnauthorizedAction{});
     }
 
-    config.cw3_address = deps.api.addr_validate(&address)?;
+    consume_action_limit(deps.storage, &hot_wallet_config, CLAIM_AIRDROP_ID, env.block.time.seconds())?;
 
-    CONFIG.save(deps.storage, &config)?;
+    //airdrop check
+    let airdrop: Airdrop = AIRDROPS.may_load(deps.storage, label.as_str())?.ok_or(ContractError::AirdropNotFound{})?;
+
+    //contract check
+    let airdrop_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), airdrop.airdrop_label.as_str())?;
+    let token_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), airdrop.token_label.as_str())?;
+
+    //mir and anc airdrops take the same claim msg
+    let mut messages = vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: airdrop_contract.address.clone(),
+        funds: vec![],
+        msg: to_binary(&MIRAirdropHandleMsg::Claim{stage, amount, proof})?,
+    })];
+
+    if swap{
+        let pair_label = airdrop.pair_label.clone().ok_or(ContractError::AirdropSwapNotConfigured{})?;
+        let pair_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), pair_label.as_str())?;
+
+        //same slippage bound as Swap
+        let belief_price = belief_price.filter(|x| !x.is_zero())
+            .ok_or_else(|| ContractError::InvalidSwap{reason: String::from("belief_price must be non-zero")})?;
+
+        //spending limits on the token address cap what gets sold; only the claim is sold, so the reserve is never touched
+        consume_spending_limits(deps.storage, &hot_wallet_config, &[Coin{denom: token_contract.address.clone(), amount}], env.block.time.seconds())?;
 
-    Ok(Response::new().add_attributes(vec![("action", "replace_multisig")]))
+        //the pair pays out to the cw20 sender, i.e. the smart wallet
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: token_contract.address.clone(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send{
+                contract: pair_contract.address,
+                amount,
+                msg: to_binary(&PairHandleMsg::Swap{
+                    belief_price: Some(belief_price),
+                    max_spread: Some(airdrop.max_spread),
+                    to: None,
+                })?,
+            })?,
+        }));
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "claim_airdrop"),
+        ("action_id", CLAIM_AIRDROP_ID.to_string().as_str()),
+        ("contract", airdrop_contract.address.as_str()),
+        ("label", label.as_str()),
+        ("denom", token_contract.address.as_str()),
+        ("amount", amount.to_string().as_str()),
+        ("swap", swap.to_string().as_str()),
+    ]).add_messages(messages))
 }
 
 #[allow(clippy::too_many_arguments)]
-pub fn execute_command(
+pub fn execute_swap(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    command: CosmosMsg,
+    pair: String,
+    offer_amount: Uint128,
+    belief_price: Decimal,
+    max_spread: Decimal,
+    min_receive: Uint128,
 ) -> Result<Response, ContractError> {
 
-    let config: Config = CONFIG.load(deps.storage)?;
+    //freeze check
+    assert_not_frozen(deps.storage)?;
 
-    //multisig check
-    if info.sender.to_string() != config.cw3_address{
-        return Err(ContractError::Unauthorized{});
+    //hot wallet check
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
+
+    //hot wallet is enabled for this action
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == SWAP_ID).is_none(){
+        return Err(ContractError::UnauthorizedAction{});
     }
 
-    Ok(Response::new().add_attributes(vec![("action", "execute_command")]).add_message(command))
-}
+    consume_action_limit(deps.storage, &hot_wallet_config, SWAP_ID, env.block.time.seconds())?;
 
+    //pair check
+    let swap_pair: SwapPair = SWAP_PAIRS.may_load(deps.storage, pair.as_str())?.ok_or(ContractError::SwapPairNotFound{})?;
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Config {} => Ok(to_binary(&query_config(deps)?)?),
-        QueryMsg::HotWallet {address} => Ok(to_binary(&query_hot_wallet_state(deps, env, address)?)?),
+    //contract check
+    let pair_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), swap_pair.contract_label.as_str())?;
+
+    //cw20 assets are sent from and balance checked against their token contract, so those have to be whitelisted too
+    for asset in [&swap_pair.offer_asset, &swap_pair.ask_asset].iter(){
+        if let AssetInfo::Token{contract_addr} = asset{
+            load_whitelisted_address(deps.as_ref(), contract_addr)?;
+        }
     }
-}
 
+    //slippage bounds
+    if belief_price < swap_pair.min_belief_price || belief_price > swap_pair.max_belief_price{
+        return Err(ContractError::InvalidSwap{reason: format!("belief_price outside the pair's {}-{}", swap_pair.min_belief_price, swap_pair.max_belief_price)});
+    }
+    if max_spread > swap_pair.max_spread{
+        return Err(ContractError::InvalidSwap{reason: format!("max_spread above the pair's {}", swap_pair.max_spread)});
+    }
 
-pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
-    let config: Config = CONFIG.load(deps.storage)?;
+    //the caller's min_receive can only raise what belief_price and max_spread already imply
+    let min_receive = max(min_receive, min_swap_return(offer_amount, belief_price, max_spread));
+    if min_receive.is_zero(){
+        return Err(ContractError::InvalidSwap{reason: String::from("offer_amount too small to receive anything")});
+    }
 
-    Ok(ConfigResponse {
-        hot_wallets: config.hot_wallets,
-        cw3_address: config.cw3_address,
-        whitelisted_contracts: config.whitelisted_contracts,
-    })
-  }
-  
+    //the reserve stays in the smart wallet
+    let offer_balance = query_asset_balance(deps.as_ref(), &swap_pair.offer_asset, env.contract.address.to_string())?;
+    assert_reserve(deps.storage, &asset_denom(&swap_pair.offer_asset), offer_balance, offer_amount)?;
 
-pub fn query_hot_wallet_state(deps: Deps, env: Env, address: String) -> StdResult<HotWalletStateResponse> {
+    //spending limits on the offer asset cap the notional swapped per window
+    consume_spending_limits(deps.storage, &hot_wallet_config, &[Coin{denom: asset_denom(&swap_pair.offer_asset), amount: offer_amount}], env.block.time.seconds())?;
 
-    let hot_wallet: HotWalletState = HOT_WALLETS.may_load(deps.storage, address)?.unwrap_or(HotWalletState{address: String::from(""), last_gas_fillup: env.block.time.seconds()});
+    //native offers are taxed on top, cw20 sends are not
+    let tax = match &swap_pair.offer_asset{
+        AssetInfo::NativeToken{denom} => amount_with_tax(&deps.querier, denom, offer_amount)? - offer_amount,
+        AssetInfo::Token{..} => Uint128::zero(),
+    };
+
+    //the reply checks what actually arrived against min_receive
+    let balance_before = query_asset_balance(deps.as_ref(), &swap_pair.ask_asset, env.contract.address.to_string())?;
+    PENDING_SWAP.save(deps.storage, &PendingSwap{
+        hot_wallet: info.sender.to_string(),
+        ask_asset: swap_pair.ask_asset.clone(),
+        balance_before,
+        min_receive,
+    })?;
+
+    //the pair pays out to the sender, i.e. the smart wallet
+    let swap_msg = match swap_pair.offer_asset.clone(){
+        AssetInfo::NativeToken{denom} => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: pair_contract.address.clone(),
+            funds: vec![Coin{denom: denom.clone(), amount: offer_amount}],
+            msg: to_binary(&PairExecuteMsg::Swap{
+                offer_asset: Asset{info: AssetInfo::NativeToken{denom}, amount: offer_amount},
+                belief_price: Some(belief_price),
+                max_spread: Some(max_spread),
+                to: None,
+            })?,
+        }),
+        AssetInfo::Token{contract_addr} => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send{
+                contract: pair_contract.address.clone(),
+                amount: offer_amount,
+                msg: to_binary(&PairCw20HookMsg::Swap{
+                    belief_price: Some(belief_price),
+                    max_spread: Some(max_spread),
+                    to: None,
+                })?,
+            })?,
+        }),
+    };
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "swap"),
+            ("action_id", SWAP_ID.to_string().as_str()),
+            ("contract", pair_contract.address.as_str()),
+            ("pair", pair.as_str()),
+            ("denom", asset_denom(&swap_pair.offer_asset).as_str()),
+            ("amount", offer_amount.to_string().as_str()),
+            ("tax", tax.to_string().as_str()),
+            ("min_receive", min_receive.to_string().as_str()),
+        ])
+        .add_submessage(SubMsg::reply_on_success(swap_msg, SWAP_REPLY_ID)))
+}
+
+fn reply_swap(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+
+    let pending: PendingSwap = PENDING_SWAP.load(deps.storage)?;
+    PENDING_SWAP.remove(deps.storage);
+
+    let balance = query_asset_balance(deps.as_ref(), &pending.ask_asset, env.contract.address.to_string())?;
+    let received = balance.checked_sub(pending.balance_before).unwrap_or_default();
+
+    //erroring here reverts the swap
+    if received < pending.min_receive{
+        return Err(ContractError::SwapBelowMinimum{received, min_receive: pending.min_receive});
+    }
+
+    let res = Response::new().add_attributes(vec![
+        ("action", "swap_received"),
+        ("action_id", SWAP_ID.to_string().as_str()),
+        ("denom", asset_denom(&pending.ask_asset).as_str()),
+        ("amount", received.to_string().as_str()),
+        ("balance", balance.to_string().as_str()),
+    ]);
+
+    record_reply(deps.storage, &env, &pending.hot_wallet, res)
+}
+
+//replies are checked and journaled like the hot action they finish
+fn record_reply(storage: &mut dyn Storage, env: &Env, hot_wallet: &str, res: Response) -> Result<Response, ContractError> {
+    assert_response_beneficiaries(storage, env.block.time.seconds(), &env.contract.address, &res)?;
+    let res = res.add_attribute("actor", hot_wallet);
+    let id = record_action(storage, env, &Addr::unchecked(hot_wallet), ActorRole::HotWallet, &res)?;
+    Ok(res.add_attribute("journal_id", id.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_borrow_stable(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+
+    //freeze check
+    assert_not_frozen(deps.storage)?;
+
+    //hot wallet check
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
+
+    //hot wallet is enabled for this action
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == ANCHOR_BORROW_STABLE_ID).is_none(){
+        return Err(ContractError::UnauthorizedAction{});
+    }
+
+    consume_action_limit(deps.storage, &hot_wallet_config, ANCHOR_BORROW_STABLE_ID, env.block.time.seconds())?;
+
+    //contract check
+    let anchor_market_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_MARKET_CONTRACT)?;
+    let anchor_overseer_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_OVERSEER_CONTRACT)?;
+    let anchor_oracle_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_ORACLE_CONTRACT)?;
+
+    let loan_config: LoanConfig = LOAN_CONFIG.may_load(deps.storage)?.ok_or(ContractError::LoanConfigNotSet{})?;
+
+    let loan = query_loan_amount(&deps.querier, &anchor_market_contract.address, env.contract.address.as_str(), env.block.height)?;
+    let collateral_value = query_collateral_value(&deps.querier, &anchor_overseer_contract.address, &anchor_oracle_contract.address, env.contract.address.as_str())?;
+
+    //borrow up to the target ltv, never past it
+    let borrow_limit = max_borrow(loan, collateral_value, loan_config.target_ltv);
+    if borrow_limit.is_zero(){
+        return Err(ContractError::TargetLtvExceeded{});
+    }
+    let borrow_amount = min(Uint256::from(amount), borrow_limit);
+
+    //borrowed uusd counts against the uusd spending limits
+    consume_spending_limits(deps.storage, &hot_wallet_config, &[Coin{denom: String::from("uusd"), amount: borrow_amount.into()}], env.block.time.seconds())?;
+
+    let borrow_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: anchor_market_contract.address.clone(),
+        funds: vec![],
+        msg: to_binary(&BorrowStable{borrow_amount, to: None})?,
+    });
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "borrow_stable"),
+        ("action_id", ANCHOR_BORROW_STABLE_ID.to_string().as_str()),
+        ("contract", anchor_market_contract.address.as_str()),
+        ("denom", "uusd"),
+        ("amount", borrow_amount.to_string().as_str()),
+    ]).add_message(borrow_msg))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_deposit_collateral(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+
+    //freeze check
+    assert_not_frozen(deps.storage)?;
+
+    //hot wallet check
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
+
+    //hot wallet is enabled for this action
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == ANCHOR_DEPOSIT_COLLATERAL_ID).is_none(){
+        return Err(ContractError::UnauthorizedAction{});
+    }
+
+    consume_action_limit(deps.storage, &hot_wallet_config, ANCHOR_DEPOSIT_COLLATERAL_ID, env.block.time.seconds())?;
+
+    //contract check
+    let bluna_token_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), BLUNA_TOKEN_CONTRACT)?;
+    let anchor_custody_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_CUSTODY_BLUNA_CONTRACT)?;
+    let anchor_overseer_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_OVERSEER_CONTRACT)?;
+
+    assert_token_reserve(deps.as_ref(), env.contract.address.as_str(), &bluna_token_contract.address, amount)?;
+
+    consume_spending_limits(deps.storage, &hot_wallet_config, &[Coin{denom: bluna_token_contract.address.clone(), amount}], env.block.time.seconds())?;
+
+    //collateral has to sit in custody before the overseer can lock it
+    let deposit_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: bluna_token_contract.address.clone(),
+        funds: vec![],
+        msg: to_binary(&Cw20ExecuteMsg::Send{
+            contract: anchor_custody_contract.address.clone(),
+            amount,
+            msg: to_binary(&DepositCollateral{})?,
+        })?,
+    });
+
+    let lock_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: anchor_overseer_contract.address,
+        funds: vec![],
+        msg: to_binary(&LockCollateral{collaterals: vec![(bluna_token_contract.address.clone(), Uint256::from(amount))]})?,
+    });
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "deposit_collateral"),
+        ("action_id", ANCHOR_DEPOSIT_COLLATERAL_ID.to_string().as_str()),
+        ("contract", anchor_custody_contract.address.as_str()),
+        ("denom", bluna_token_contract.address.as_str()),
+        ("amount", amount.to_string().as_str()),
+    ]).add_messages(vec![deposit_msg, lock_msg]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_withdraw_collateral(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+
+    //freeze check
+    assert_not_frozen(deps.storage)?;
+
+    //hot wallet check
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
+
+    //hot wallet is enabled for this action
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == ANCHOR_WITHDRAW_COLLATERAL_ID).is_none(){
+        return Err(ContractError::UnauthorizedAction{});
+    }
+
+    consume_action_limit(deps.storage, &hot_wallet_config, ANCHOR_WITHDRAW_COLLATERAL_ID, env.block.time.seconds())?;
+
+    //contract check
+    let bluna_token_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), BLUNA_TOKEN_CONTRACT)?;
+    let anchor_custody_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_CUSTODY_BLUNA_CONTRACT)?;
+    let anchor_overseer_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_OVERSEER_CONTRACT)?;
+    let anchor_market_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_MARKET_CONTRACT)?;
+    let anchor_oracle_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_ORACLE_CONTRACT)?;
+
+    //the remaining collateral must still cover the loan at the target ltv
+    let loan = query_loan_amount(&deps.querier, &anchor_market_contract.address, env.contract.address.as_str(), env.block.height)?;
+    if !loan.is_zero(){
+        let loan_config: LoanConfig = LOAN_CONFIG.may_load(deps.storage)?.ok_or(ContractError::LoanConfigNotSet{})?;
+
+        let collateral_value = query_collateral_value(&deps.querier, &anchor_overseer_contract.address, &anchor_oracle_contract.address, env.contract.address.as_str())?;
+        let withdraw_value = Uint256::from(amount) * query_price(&deps.querier, &anchor_oracle_contract.address, &bluna_token_contract.address)?;
+
+        if withdraw_value > collateral_value || (collateral_value - withdraw_value) * loan_config.target_ltv < loan{
+            return Err(ContractError::TargetLtvExceeded{});
+        }
+    }
+
+    //unlocking collateral is capped like spending it
+    consume_spending_limits(deps.storage, &hot_wallet_config, &[Coin{denom: bluna_token_contract.address.clone(), amount}], env.block.time.seconds())?;
+
+    let unlock_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: anchor_overseer_contract.address,
+        funds: vec![],
+        msg: to_binary(&UnlockCollateral{collaterals: vec![(bluna_token_contract.address.clone(), Uint256::from(amount))]})?,
+    });
+
+    let withdraw_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: anchor_custody_contract.address.clone(),
+        funds: vec![],
+        msg: to_binary(&WithdrawCollateral{amount: Some(Uint256::from(amount))})?,
+    });
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "withdraw_collateral"),
+        ("action_id", ANCHOR_WITHDRAW_COLLATERAL_ID.to_string().as_str()),
+        ("contract", anchor_custody_contract.address.as_str()),
+        ("denom", bluna_token_contract.address.as_str()),
+        ("amount", amount.to_string().as_str()),
+    ]).add_messages(vec![unlock_msg, withdraw_msg]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_rebalance_loan(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+
+    //freeze check
+    assert_not_frozen(deps.storage)?;
+
+    //hot wallet check
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
+
+    //hot wallet is enabled for this action
+    if hot_wallet_config.whitelisted_messages.iter().find(|&&x| x == ANCHOR_REBALANCE_LOAN_ID).is_none(){
+        return Err(ContractError::UnauthorizedAction{});
+    }
+
+    consume_action_limit(deps.storage, &hot_wallet_config, ANCHOR_REBALANCE_LOAN_ID, env.block.time.seconds())?;
+
+    //contract check
+    let anchor_market_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_MARKET_CONTRACT)?;
+    let anchor_overseer_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_OVERSEER_CONTRACT)?;
+    let anchor_oracle_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_ORACLE_CONTRACT)?;
+
+    let loan_config: LoanConfig = LOAN_CONFIG.may_load(deps.storage)?.ok_or(ContractError::LoanConfigNotSet{})?;
+
+    let loan = query_loan_amount(&deps.querier, &anchor_market_contract.address, env.contract.address.as_str(), env.block.height)?;
+    let collateral_value = query_collateral_value(&deps.querier, &anchor_overseer_contract.address, &anchor_oracle_contract.address, env.contract.address.as_str())?;
+
+    //repay exactly what brings the loan back to the safe ltv
+    let repay_amount: Uint128 = repay_to_safe_ltv(loan, collateral_value, loan_config.safe_ltv).into();
+    if repay_amount.is_zero(){
+        return Err(ContractError::LoanWithinSafeLtv{});
+    }
+
+    //the reserve stays in the smart wallet
+    let smart_wallet_balance = query_balance(deps.as_ref(), env.contract.address.to_string(), String::from("uusd"))?;
+    let available = spendable(deps.storage, "uusd", smart_wallet_balance)?;
+
+    //the repay send is taxed on top
+    let repay_cost = amount_with_tax(&deps.querier, "uusd", repay_amount)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut redeem_amount = Uint256::zero();
+
+    //redeem the shortfall from aust; the market pays out before the repay runs
+    if available < repay_cost{
+        let anchor_aust_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), ANCHOR_AUST_CONTRACT)?;
+
+        let exchange_rate = query_aust_exchange_rate(&deps.querier, &anchor_market_contract.address, env.block.height)?;
+        redeem_amount = aust_to_redeem(&deps.querier, repay_cost - available, exchange_rate)?;
+
+        let aust_balance = query_token_balance(deps.as_ref(), anchor_aust_contract.address.clone(), env.contract.address.to_string())?;
+        if Uint256::from(aust_balance) < redeem_amount{
+            return Err(ContractError::InsufficientRepayFunds{amount: repay_amount.to_string()});
+        }
+        assert_reserve(deps.storage, &anchor_aust_contract.address, aust_balance, redeem_amount.into())?;
+
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: anchor_aust_contract.address,
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send{
+                contract: anchor_market_contract.address.clone(),
+                amount: redeem_amount.into(),
+                msg: to_binary(&RedeemStable{})?,
+            })?,
+        }));
+    }
+
+    let funds = vec![Coin{
+        denom: String::from("uusd"),
+        amount: repay_amount,
+    }];
+
+    consume_spending_limits(deps.storage, &hot_wallet_config, &funds, env.block.time.seconds())?;
+
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: anchor_market_contract.address.clone(),
+        funds,
+        msg: to_binary(&RepayStable{})?,
+    }));
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "rebalance_loan"),
+        ("action_id", ANCHOR_REBALANCE_LOAN_ID.to_string().as_str()),
+        ("contract", anchor_market_contract.address.as_str()),
+        ("denom", "uusd"),
+        ("amount", repay_amount.to_string().as_str()),
+        ("tax", (repay_cost - repay_amount).to_string().as_str()),
+        ("redeem_amount", redeem_amount.to_string().as_str()),
+    ]).add_messages(messages))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_run_action(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    params: Vec<ActionParam>,
+) -> Result<Response, ContractError> {
+
+    //freeze check
+    assert_not_frozen(deps.storage)?;
+
+    //hot wallet check
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
+
+    //hot wallet is enabled for this action
+    if !hot_wallet_config.whitelisted_messages.contains(&id){
+        return Err(ContractError::UnauthorizedAction{});
+    }
+
+    consume_action_limit(deps.storage, &hot_wallet_config, id, env.block.time.seconds())?;
+
+    let template: ActionTemplate = ACTION_TEMPLATES
+        .may_load(deps.storage, U64Key::from(id))?
+        .ok_or(ContractError::ActionTemplateNotFound{})?;
+
+    //contract check
+    let target_contract: WhitelistedContract = load_whitelisted_contract(deps.as_ref(), &template.contract_label)?;
+
+    let (msg, funds) = render_action(deps.as_ref(), &template, &params)?;
+    let msg = Binary::from(msg.into_bytes());
+
+    assert_coin_reserves(deps.as_ref(), env.contract.address.as_str(), &funds)?;
+
+    //a rendered cw20 transfer, send or allowance has to leave the token's reserve behind as well
+    if let Some(amount) = cw20_amount(&msg){
+        assert_token_reserve(deps.as_ref(), env.contract.address.as_str(), &target_contract.address, amount)?;
+    }
+
+    let funds_attribute = list_attribute(&funds);
+
+    let action_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: target_contract.address.clone(),
+        funds,
+        msg,
+    });
+
+    //cw20 amounts the template renders count against the limits keyed by token address
+    consume_spending_limits(deps.storage, &hot_wallet_config, &command_funds(&action_msg), env.block.time.seconds())?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "run_action"),
+        ("action_id", id.to_string().as_str()),
+        ("contract", target_contract.address.as_str()),
+        ("template", template.label.as_str()),
+        ("funds", funds_attribute.as_str()),
+    ]).add_message(action_msg))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_fill_up_gas(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+
+    //freeze check
+    assert_not_frozen(deps.storage)?;
+
+    //hot wallet check
+    let hot_wallet_config: HotWallet = hot_wallet_configs()
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or(ContractError::Unauthorized{})?;
+
+    let mut hot_wallet_state = HOT_WALLETS
+        .may_load(deps.storage, info.sender.to_string())?
+        .unwrap_or(
+            HotWalletState{
+                address: info.sender.to_string(), 
+                last_gas_fillup: env.block.time.seconds() - hot_wallet_config.gas_cooldown - 10u64,
+        });
+
+    //cooldown check
+    if hot_wallet_state.last_gas_fillup + hot_wallet_config.gas_cooldown > env.block.time.seconds(){
+        return Err(ContractError::GasCooldown{});
+    }
+
+    //figure out how much gas is needed to fill each of the hot wallet's tanks
+    let tanks: Vec<GasTankStatus> = hot_wallet_config.gas_tanks
+        .iter()
+        .map(|gas_tank| gas_tank_status(deps.as_ref(), env.contract.address.as_str(), info.sender.as_str(), gas_tank))
+        .filter(|x| x.as_ref().map_or(true, |x| !x.deficit.is_zero()))
+        .collect::<StdResult<Vec<GasTankStatus>>>()?;
+
+    if tanks.is_empty(){
+        return Err(ContractError::GasTankFull{});
+    }
+
+    //tax comes out of the sent amount, so the full need counts against the reserve and the limit
+    let needs: Vec<Coin> = tanks.iter().map(|x| Coin{denom: x.denom.clone(), amount: x.deficit}).collect();
+    assert_coin_reserves(deps.as_ref(), env.contract.address.as_str(), &needs)?;
+
+    //sufficient smart_wallet balance check, every tank or none
+    if tanks.iter().any(|x| !x.refill_funded){
+        return Err(ContractError::SmartWalletGas{});
+    }
+
+    consume_spending_limits(deps.storage, &hot_wallet_config, &needs, env.block.time.seconds())?;
+
+    let gas_sent: Vec<Coin> = tanks.iter().map(|x| Coin{denom: x.denom.clone(), amount: x.deficit - x.tax}).collect();
+    let taxes: Vec<Coin> = tanks.iter().map(|x| Coin{denom: x.denom.clone(), amount: x.tax}).collect();
+
+    let bank_msg = CosmosMsg::Bank(BankMsg::Send{
+        to_address: info.sender.to_string(),
+        amount: gas_sent.clone(),
+    });
+
+    hot_wallet_state.last_gas_fillup = env.block.time.seconds();
+
+    HOT_WALLETS.save(deps.storage, info.sender.to_string(), &hot_wallet_state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "fill_up_gas"),
+        ("recipient", info.sender.as_str()),
+        ("amount", list_attribute(&gas_sent).as_str()),
+        ("tax", list_attribute(&taxes).as_str()),
+    ]).add_message(bank_msg))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_remove_hot(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    //check if valid hot address
+    if hot_wallet_configs().may_load(deps.storage, address.as_str())?.is_none(){
+        return Err(ContractError::InvalidHotAddress{});
+    }
+
+    //remove from state
+    HOT_WALLETS.remove(deps.storage, address.clone());
+
+    //remove from config
+    hot_wallet_configs().remove(deps.storage, address.as_str())?;
+
+    Ok(Response::new().add_attributes(vec![("action", "remove_hot"), ("address", address.as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_upsert_hot(
+    deps: DepsMut,
+    info: MessageInfo,
+    hot_wallet: HotWallet,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    //check if valid hot address
+    let address: Addr = deps.api.addr_validate(&hot_wallet.address)?;
+    validate_gas_tanks(&hot_wallet.gas_tanks)?;
+    validate_spending_limits(&hot_wallet.spending_limits)?;
+    validate_action_limits(&hot_wallet.action_limits)?;
+
+    hot_wallet_configs().save(deps.storage, address.as_str(), &hot_wallet)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "upsert_hot"), ("address", address.as_str()), ("label", hot_wallet.label.as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_replace_contracts(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contracts: Vec<WhitelistedContract>,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    validate_whitelisted_contracts(deps.as_ref(), &contracts)?;
+
+    //full replacement is the one place that still walks every entry
+    let labels: Vec<String> = whitelisted_contracts()
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, contract)| contract.label))
+        .collect::<StdResult<Vec<String>>>()?;
+
+    for label in labels.iter(){
+        whitelisted_contracts().remove(deps.storage, label.as_str())?;
+    }
+
+    for contract in contracts.iter(){
+        whitelisted_contracts().save(deps.storage, contract.label.as_str(), contract)?;
+    }
+    sync_whitelist_activation(deps.storage, env.block.time.seconds())?;
+
+    let labels: Vec<&str> = contracts.iter().map(|x| x.label.as_str()).collect();
+
+    Ok(Response::new().add_attributes(vec![("action", "replace_contracts"), ("labels", list_attribute(&labels).as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_add_whitelisted_contract(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: WhitelistedContract,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if whitelisted_contracts().may_load(deps.storage, contract.label.as_str())?.is_some(){
+        return Err(ContractError::DuplicateContractLabel{label: contract.label});
+    }
+
+    validate_whitelisted_contract(deps.as_ref(), &contract)?;
+    whitelisted_contracts().save(deps.storage, contract.label.as_str(), &contract)?;
+
+    //funds only go to a new address once the beneficiary policy's delay has passed
+    sync_whitelist_activation(deps.storage, env.block.time.seconds())?;
+    let active_at = WHITELIST_ACTIVE_AT.load(deps.storage, contract.address.as_str())?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "add_whitelisted_contract"),
+        ("label", contract.label.as_str()),
+        ("contract", contract.address.as_str()),
+        ("code_id", contract.code_id.to_string().as_str()),
+        ("active_at", active_at.to_string().as_str()),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_update_whitelisted_contract(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: WhitelistedContract,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if whitelisted_contracts().may_load(deps.storage, contract.label.as_str())?.is_none(){
+        return Err(ContractError::WhitelistedContractNotFound{});
+    }
+
+    validate_whitelisted_contract(deps.as_ref(), &contract)?;
+    whitelisted_contracts().save(deps.storage, contract.label.as_str(), &contract)?;
+
+    //funds only go to a new address once the beneficiary policy's delay has passed
+    sync_whitelist_activation(deps.storage, env.block.time.seconds())?;
+    let active_at = WHITELIST_ACTIVE_AT.load(deps.storage, contract.address.as_str())?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_whitelisted_contract"),
+        ("label", contract.label.as_str()),
+        ("contract", contract.address.as_str()),
+        ("code_id", contract.code_id.to_string().as_str()),
+        ("active_at", active_at.to_string().as_str()),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_remove_whitelisted_contract(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    label: String,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if whitelisted_contracts().may_load(deps.storage, label.as_str())?.is_none(){
+        return Err(ContractError::WhitelistedContractNotFound{});
+    }
+
+    whitelisted_contracts().remove(deps.storage, label.as_str())?;
+    sync_whitelist_activation(deps.storage, env.block.time.seconds())?;
+
+    Ok(Response::new().add_attributes(vec![("action", "remove_whitelisted_contract"), ("label", label.as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_update_bond_validators(
+    deps: DepsMut,
+    info: MessageInfo,
+    validators: Vec<String>,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    validate_bond_validators(&validators)?;
+
+    BOND_VALIDATORS.save(deps.storage, &validators)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "update_bond_validators"), ("validators", list_attribute(&validators).as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_upsert_airdrop(
+    deps: DepsMut,
+    info: MessageInfo,
+    airdrop: Airdrop,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    validate_airdrop(&airdrop)?;
+
+    AIRDROPS.save(deps.storage, airdrop.label.as_str(), &airdrop)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "upsert_airdrop"), ("label", airdrop.label.as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_remove_airdrop(
+    deps: DepsMut,
+    info: MessageInfo,
+    label: String,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if AIRDROPS.may_load(deps.storage, label.as_str())?.is_none(){
+        return Err(ContractError::AirdropNotFound{});
+    }
+
+    AIRDROPS.remove(deps.storage, label.as_str());
+
+    Ok(Response::new().add_attributes(vec![("action", "remove_airdrop"), ("label", label.as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_upsert_swap_pair(
+    deps: DepsMut,
+    info: MessageInfo,
+    swap_pair: SwapPair,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    validate_swap_pair(&swap_pair)?;
+
+    SWAP_PAIRS.save(deps.storage, swap_pair.label.as_str(), &swap_pair)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "upsert_swap_pair"), ("label", swap_pair.label.as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_remove_swap_pair(
+    deps: DepsMut,
+    info: MessageInfo,
+    label: String,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if SWAP_PAIRS.may_load(deps.storage, label.as_str())?.is_none(){
+        return Err(ContractError::SwapPairNotFound{});
+    }
+
+    SWAP_PAIRS.remove(deps.storage, label.as_str());
+
+    Ok(Response::new().add_attributes(vec![("action", "remove_swap_pair"), ("label", label.as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_update_loan_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    loan_config: LoanConfig,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    validate_loan_config(&loan_config)?;
+
+    LOAN_CONFIG.save(deps.storage, &loan_config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_loan_config"),
+        ("target_ltv", loan_config.target_ltv.to_string().as_str()),
+        ("safe_ltv", loan_config.safe_ltv.to_string().as_str()),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_add_action_template(
+    deps: DepsMut,
+    info: MessageInfo,
+    template: ActionTemplate,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    validate_template(&template)?;
+
+    let id = NEXT_ACTION_TEMPLATE_ID.may_load(deps.storage)?.unwrap_or(FIRST_ACTION_TEMPLATE_ID);
+
+    ACTION_TEMPLATES.save(deps.storage, U64Key::from(id), &template)?;
+    NEXT_ACTION_TEMPLATE_ID.save(deps.storage, &(id + 1))?;
+
+    Ok(Response::new().add_attributes(vec![("action", "add_action_template"), ("id", id.to_string().as_str()), ("label", template.label.as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_update_action_template(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    template: ActionTemplate,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if !ACTION_TEMPLATES.has(deps.storage, U64Key::from(id)){
+        return Err(ContractError::ActionTemplateNotFound{});
+    }
+
+    validate_template(&template)?;
+
+    ACTION_TEMPLATES.save(deps.storage, U64Key::from(id), &template)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "update_action_template"), ("id", id.to_string().as_str()), ("label", template.label.as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_remove_action_template(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if !ACTION_TEMPLATES.has(deps.storage, U64Key::from(id)){
+        return Err(ContractError::ActionTemplateNotFound{});
+    }
+
+    //ids are never reused, so hot wallets still pointing at this id simply lose the action
+    ACTION_TEMPLATES.remove(deps.storage, U64Key::from(id));
+
+    Ok(Response::new().add_attributes(vec![("action", "remove_action_template"), ("id", id.to_string().as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_replace_multisig(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    let command = TimelockedCommand::ReplaceMultisig{address: deps.api.addr_validate(&address)?};
+
+    //timelock check
+    if let Some(timelock) = TIMELOCK.may_load(deps.storage)?.filter(|x| requires_timelock(Some(x), &command)){
+        return queue_command(deps.storage, env, &timelock, command);
+    }
+
+    run_command(deps, env, command)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_update_guardians(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardians: Vec<String>,
+    threshold: u64,
+    delay: u64,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    let guardian_config = validate_guardians(deps.api, &guardians, threshold, delay)?;
+
+    GUARDIANS.save(deps.storage, &guardian_config)?;
+
+    //approvals from the old guardian set no longer count
+    clear_recovery_proposals(deps.storage)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_guardians"),
+        ("guardians", list_attribute(&guardians).as_str()),
+        ("threshold", threshold.to_string().as_str()),
+        ("delay", delay.to_string().as_str()),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_propose_recovery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_cw3_address: String,
+) -> Result<Response, ContractError> {
+
+    //guardian check
+    let guardian_config: GuardianConfig = load_guardians(deps.storage)?;
+    if !guardian_config.guardians.contains(&info.sender){
+        return Err(ContractError::Unauthorized{});
+    }
+
+    let id = NEXT_RECOVERY_PROPOSAL_ID.may_load(deps.storage)?.unwrap_or_default();
+
+    let mut proposal = RecoveryProposal{
+        id,
+        new_cw3_address: deps.api.addr_validate(&new_cw3_address)?,
+        approvals: vec![info.sender],
+        proposed_at: env.block.time.seconds(),
+        executable_at: None,
+    };
+
+    if proposal.approvals.len() as u64 >= guardian_config.threshold{
+        proposal.executable_at = Some(env.block.time.seconds() + guardian_config.delay);
+    }
+
+    RECOVERY_PROPOSALS.save(deps.storage, U64Key::from(id), &proposal)?;
+    NEXT_RECOVERY_PROPOSAL_ID.save(deps.storage, &(id + 1))?;
+
+    Ok(Response::new().add_attributes(vec![("action", "propose_recovery"), ("id", id.to_string().as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_approve_recovery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+
+    //guardian check
+    let guardian_config: GuardianConfig = load_guardians(deps.storage)?;
+    if !guardian_config.guardians.contains(&info.sender){
+        return Err(ContractError::Unauthorized{});
+    }
+
+    let mut proposal: RecoveryProposal = RECOVERY_PROPOSALS
+        .may_load(deps.storage, U64Key::from(id))?
+        .ok_or(ContractError::RecoveryProposalNotFound{})?;
+
+    if proposal.approvals.contains(&info.sender){
+        return Err(ContractError::RecoveryAlreadyApproved{});
+    }
+
+    proposal.approvals.push(info.sender);
+
+    //the veto window starts once the threshold is reached
+    if proposal.executable_at.is_none() && proposal.approvals.len() as u64 >= guardian_config.threshold{
+        proposal.executable_at = Some(env.block.time.seconds() + guardian_config.delay);
+    }
+
+    RECOVERY_PROPOSALS.save(deps.storage, U64Key::from(id), &proposal)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "approve_recovery"), ("id", id.to_string().as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_veto_recovery(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if !RECOVERY_PROPOSALS.has(deps.storage, U64Key::from(id)){
+        return Err(ContractError::RecoveryProposalNotFound{});
+    }
+
+    RECOVERY_PROPOSALS.remove(deps.storage, U64Key::from(id));
+
+    Ok(Response::new().add_attributes(vec![("action", "veto_recovery"), ("id", id.to_string().as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_recovery(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+) -> Result<Response, ContractError> {
+
+    let proposal: RecoveryProposal = RECOVERY_PROPOSALS
+        .may_load(deps.storage, U64Key::from(id))?
+        .ok_or(ContractError::RecoveryProposalNotFound{})?;
+
+    //approvals and the veto window were both settled before anyone can trigger this
+    let executable_at = proposal.executable_at.ok_or(ContractError::RecoveryNotApproved{})?;
+    if executable_at > env.block.time.seconds(){
+        return Err(ContractError::RecoveryDelayNotDone{executable_at});
+    }
+
+    replace_multisig(deps.storage, proposal.new_cw3_address.clone())?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "execute_recovery"),
+        ("id", id.to_string().as_str()),
+        ("cw3_address", proposal.new_cw3_address.as_str()),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_freeze(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    reason: String,
+) -> Result<Response, ContractError> {
+
+    //hot wallet or guardian check
+    if hot_wallet_configs().may_load(deps.storage, info.sender.as_str())?.is_none() && !load_guardians(deps.storage)?.guardians.contains(&info.sender){
+        return Err(ContractError::Unauthorized{});
+    }
+
+    //keep the first freeze on record
+    if FROZEN.may_load(deps.storage)?.is_some(){
+        return Err(ContractError::Frozen{});
+    }
+
+    FROZEN.save(deps.storage, &FreezeInfo{
+        reason,
+        initiator: info.sender.clone(),
+        frozen_at: env.block.time.seconds(),
+    })?;
+
+    Ok(Response::new().add_attributes(vec![("action", "freeze"), ("initiator", info.sender.as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_unfreeze(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if FROZEN.may_load(deps.storage)?.is_none(){
+        return Err(ContractError::NotFrozen{});
+    }
+
+    FROZEN.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![("action", "unfreeze")]))
+}
+
+fn assert_not_frozen(storage: &dyn Storage) -> Result<(), ContractError> {
+    if FROZEN.may_load(storage)?.is_some(){
+        return Err(ContractError::Frozen{});
+    }
+    Ok(())
+}
+
+fn load_guardians(storage: &dyn Storage) -> StdResult<GuardianConfig> {
+    Ok(GUARDIANS.may_load(storage)?.unwrap_or(GuardianConfig{guardians: vec![], threshold: 0u64, delay: 0u64}))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_command(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    command: CosmosMsg,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    //beneficiary check
+    assert_beneficiaries(deps.storage, env.block.time.seconds(), &env.contract.address, &command)?;
+
+    let command = TimelockedCommand::Execute{command};
+
+    //timelock check
+    if let Some(timelock) = TIMELOCK.may_load(deps.storage)?.filter(|x| requires_timelock(Some(x), &command)){
+        return queue_command(deps.storage, env, &timelock, command);
+    }
+
+    run_command(deps, env, command)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_receive_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+
+    //anyone can call the hook, so only tokens the multisig added are tracked
+    let tracked = TRACKED_TOKENS.has(deps.storage, info.sender.as_str());
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "receive_cw20"),
+        ("token", info.sender.as_str()),
+        ("from", cw20_msg.sender.as_str()),
+        ("amount", cw20_msg.amount.to_string().as_str()),
+        ("tracked", tracked.to_string().as_str()),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_add_tracked_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token: String,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    let token = deps.api.addr_validate(&token)?;
+
+    //has to answer a cw20 balance query, or the Cw20Balances query would break on it
+    query_token_balance(deps.as_ref(), token.to_string(), env.contract.address.to_string())?;
+
+    TRACKED_TOKENS.save(deps.storage, token.as_str(), &Empty{})?;
+
+    Ok(Response::new().add_attributes(vec![("action", "add_tracked_token"), ("token", token.as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_remove_tracked_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    token: String,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if TRACKED_TOKENS.may_load(deps.storage, token.as_str())?.is_none(){
+        return Err(ContractError::TokenNotTracked{});
+    }
+
+    TRACKED_TOKENS.remove(deps.storage, token.as_str());
+
+    Ok(Response::new().add_attributes(vec![("action", "remove_tracked_token"), ("token", token.as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_withdraw_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token: String,
+    amount: Uint128,
+    recipient: String,
+) -> Result<Response, ContractError> {
+
+    let token = deps.api.addr_validate(&token)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    //same multisig check and timelock as a hand-crafted Execute
+    let command = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: token.to_string(),
+        funds: vec![],
+        msg: to_binary(&Cw20ExecuteMsg::Transfer{recipient: recipient.to_string(), amount})?,
+    });
+
+    execute_command(deps, env, info, command)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_update_timelock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    timelock: Option<TimelockConfig>,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if let Some(timelock) = &timelock{
+        validate_timelock(timelock)?;
+    }
+
+    let command = TimelockedCommand::UpdateTimelock{timelock};
+
+    //timelock check
+    if let Some(timelock) = TIMELOCK.may_load(deps.storage)?.filter(|x| requires_timelock(Some(x), &command)){
+        return queue_command(deps.storage, env, &timelock, command);
+    }
+
+    run_command(deps, env, command)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_cancel_queued(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig or guardian check
+    if info.sender != config.cw3_address && !load_guardians(deps.storage)?.guardians.contains(&info.sender){
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if !QUEUED_COMMANDS.has(deps.storage, U64Key::from(id)){
+        return Err(ContractError::QueuedCommandNotFound{});
+    }
+
+    QUEUED_COMMANDS.remove(deps.storage, U64Key::from(id));
+
+    Ok(Response::new().add_attributes(vec![("action", "cancel_queued"), ("id", id.to_string().as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_queued(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+) -> Result<Response, ContractError> {
+
+    //freeze check; only cancelling stays possible while frozen
+    assert_not_frozen(deps.storage)?;
+
+    let queued: QueuedCommand = QUEUED_COMMANDS
+        .may_load(deps.storage, U64Key::from(id))?
+        .ok_or(ContractError::QueuedCommandNotFound{})?;
+
+    if queued.eta > env.block.time.seconds(){
+        return Err(ContractError::TimelockNotDone{eta: queued.eta});
+    }
+
+    //beneficiaries may have been removed since the command was queued
+    if let TimelockedCommand::Execute{command} = &queued.command{
+        assert_beneficiaries(deps.storage, env.block.time.seconds(), &env.contract.address, command)?;
+    }
+
+    QUEUED_COMMANDS.remove(deps.storage, U64Key::from(id));
+
+    Ok(run_command(deps, env, queued.command)?.add_attribute("id", id.to_string()))
+}
+
+/// a list as a single attribute value, e.g. "100uusd,5uluna"; "none" when empty, empty values are rejected
+fn list_attribute<T: ToString>(items: &[T]) -> String {
+    if items.is_empty(){
+        return String::from("none");
+    }
+    items.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(",")
+}
+
+fn queue_command(
+    storage: &mut dyn Storage,
+    env: Env,
+    timelock: &TimelockConfig,
+    command: TimelockedCommand,
+) -> Result<Response, ContractError> {
+
+    let id = NEXT_QUEUED_COMMAND_ID.may_load(storage)?.unwrap_or_default();
+    let eta = env.block.time.seconds() + timelock.delay;
+
+    QUEUED_COMMANDS.save(storage, U64Key::from(id), &QueuedCommand{
+        id,
+        command,
+        queued_at: env.block.time.seconds(),
+        eta,
+    })?;
+    NEXT_QUEUED_COMMAND_ID.save(storage, &(id + 1))?;
+
+    Ok(Response::new().add_attributes(vec![("action", "queue_command"), ("id", id.to_string().as_str()), ("eta", eta.to_string().as_str())]))
+}
+
+fn run_command(
+    deps: DepsMut,
+    env: Env,
+    command: TimelockedCommand,
+) -> Result<Response, ContractError> {
+
+    match command{
+        TimelockedCommand::Execute{command} => {
+            Ok(Response::new().add_attributes(vec![
+                ("action", "execute_command"),
+                ("target", command_target(&command).unwrap_or_else(|| String::from("none")).as_str()),
+                ("amount", list_attribute(&command_funds(&command)).as_str()),
+            ]).add_message(command))
+        },
+        TimelockedCommand::ReplaceMultisig{address} => {
+            replace_multisig(deps.storage, address.clone())?;
+            Ok(Response::new().add_attributes(vec![("action", "replace_multisig"), ("cw3_address", address.as_str())]))
+        },
+        TimelockedCommand::UpdateTimelock{timelock} => {
+            let delay = match timelock{
+                Some(timelock) => {
+                    TIMELOCK.save(deps.storage, &timelock)?;
+                    timelock.delay.to_string()
+                },
+                None => {
+                    TIMELOCK.remove(deps.storage);
+                    String::from("none")
+                },
+            };
+            Ok(Response::new().add_attributes(vec![("action", "update_timelock"), ("delay", delay.as_str())]))
+        },
+        //runs as the current multisig; a queued update can't outlive the multisig that queued it
+        TimelockedCommand::UpdateConfig{msg} => {
+            let info = MessageInfo{sender: CONFIG.load(deps.storage)?.cw3_address, funds: vec![]};
+            match *msg{
+                ExecuteMsg::UpsertHot {hot_wallet} => execute_upsert_hot(deps, info, hot_wallet),
+                ExecuteMsg::ReplaceContractWhitelist { whitelisted_contracts } => execute_replace_contracts(deps, env, info, whitelisted_contracts),
+                ExecuteMsg::AddWhitelistedContract {contract} => execute_add_whitelisted_contract(deps, env, info, contract),
+                ExecuteMsg::UpdateWhitelistedContract {contract} => execute_update_whitelisted_contract(deps, env, info, contract),
+                ExecuteMsg::AddActionTemplate {template} => execute_add_action_template(deps, info, template),
+                ExecuteMsg::UpdateActionTemplate {id, template} => execute_update_action_template(deps, info, id, template),
+                ExecuteMsg::UpsertSwapPair {swap_pair} => execute_upsert_swap_pair(deps, info, swap_pair),
+                ExecuteMsg::UpsertAirdrop {airdrop} => execute_upsert_airdrop(deps, info, airdrop),
+                _ => Err(StdError::generic_err("not a timelocked config update").into()),
+            }
+        },
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_update_beneficiary_policy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    policy: Option<BeneficiaryPolicy>,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if let Some(policy) = &policy{
+        validate_beneficiary_policy(policy)?;
+    }
+
+    let delay = policy.as_ref().map_or(String::from("none"), |x| x.delay.to_string());
+    let response = Response::new().add_attributes(vec![("action", "update_beneficiary_policy"), ("delay", delay.as_str())]);
+
+    match update_beneficiary_policy(deps.storage, env.block.time.seconds(), policy)?{
+        Some(pending) => Ok(response.add_attribute("effective_at", pending.effective_at.to_string())),
+        None => Ok(response),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_add_beneficiary(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    let address = deps.api.addr_validate(&address)?;
+
+    let now = env.block.time.seconds();
+    let active_at = now + load_beneficiary_policy(deps.storage, now)?.map_or(0, |x| x.delay);
+
+    //re-adding doesn't move an existing beneficiary's activation
+    let active_at = BENEFICIARIES.may_load(deps.storage, address.as_str())?.map_or(active_at, |x| min(x, active_at));
+    BENEFICIARIES.save(deps.storage, address.as_str(), &active_at)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "add_beneficiary"),
+        ("address", address.as_str()),
+        ("active_at", active_at.to_string().as_str()),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_remove_beneficiary(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if !BENEFICIARIES.has(deps.storage, address.as_str()){
+        return Err(ContractError::BeneficiaryNotFound{});
+    }
+
+    BENEFICIARIES.remove(deps.storage, address.as_str());
+
+    Ok(Response::new().add_attributes(vec![("action", "remove_beneficiary"), ("address", address.as_str())]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_update_reserves(
+    deps: DepsMut,
+    info: MessageInfo,
+    reserves: Vec<Coin>,
+) -> Result<Response, ContractError> {
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    //multisig check
+    if info.sender != config.cw3_address{
+        return Err(ContractError::Unauthorized{});
+    }
+
+    validate_reserves(&reserves)?;
+    save_reserves(deps.storage, &reserves)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "update_reserves"), ("reserves", list_attribute(&reserves).as_str())]))
+}
+
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => Ok(to_binary(&query_config(deps)?)?),
+        QueryMsg::GasTankStatus {address} => Ok(to_binary(&query_gas_tank_status(deps, env, address)?)?),
+        QueryMsg::HotWalletConfig {address} => Ok(to_binary(&hot_wallet_configs().load(deps.storage, address.as_str())?)?),
+        QueryMsg::HotWallets {start_after, limit} => Ok(to_binary(&query_hot_wallets(deps, start_after, limit)?)?),
+        QueryMsg::HotWalletsByLabel {label, start_after, limit} => Ok(to_binary(&query_hot_wallets_by_label(deps, label, start_after, limit)?)?),
+        QueryMsg::HotWalletsByAction {action_id, start_after, limit} => Ok(to_binary(&query_hot_wallets_by_action(deps, action_id, start_after, limit)?)?),
+        QueryMsg::WhitelistedContract {label} => Ok(to_binary(&whitelisted_contracts().load(deps.storage, label.as_str())?)?),
+        QueryMsg::WhitelistedContracts {start_after, limit} => Ok(to_binary(&query_whitelisted_contracts(deps, start_after, limit)?)?),
+        QueryMsg::WhitelistedContractsByAddress {address, start_after, limit} => Ok(to_binary(&query_whitelisted_contracts_by_address(deps, address, start_after, limit)?)?),
+        QueryMsg::WhitelistDrift {start_after, limit} => Ok(to_binary(&query_whitelist_drift(deps, start_after, limit)?)?),
+        QueryMsg::ActionTemplate {id} => Ok(to_binary(&query_action_template(deps, id)?)?),
+        QueryMsg::ActionTemplates {start_after, limit} => Ok(to_binary(&query_action_templates(deps, start_after, limit)?)?),
+        QueryMsg::SpendingAllowance {address} => Ok(to_binary(&query_spending_allowance(deps, env, address)?)?),
+        QueryMsg::ActionState {address, action_id} => Ok(to_binary(&query_action_state(deps, env, address, action_id)?)?),
+        QueryMsg::LoanConfig {} => Ok(to_binary(&LoanConfigResponse{loan_config: LOAN_CONFIG.may_load(deps.storage)?})?),
+        QueryMsg::Airdrops {start_after, limit} => Ok(to_binary(&query_airdrops(deps, start_after, limit)?)?),
+        QueryMsg::Cw20Balances {start_after, limit} => Ok(to_binary(&query_cw20_balances(deps, env, start_after, limit)?)?),
+        QueryMsg::SwapPairs {start_after, limit} => Ok(to_binary(&query_swap_pairs(deps, start_after, limit)?)?),
+        QueryMsg::BondValidators {} => Ok(to_binary(&BondValidatorsResponse{validators: BOND_VALIDATORS.may_load(deps.storage)?.unwrap_or_default()})?),
+        QueryMsg::WithdrawableUnbonded {} => Ok(to_binary(&query_withdrawable(deps, env)?)?),
+        QueryMsg::UnbondRequests {} => Ok(to_binary(&query_unbond_requests_of_wallet(deps, env)?)?),
+        QueryMsg::Freeze {} => Ok(to_binary(&FreezeResponse{freeze: FROZEN.may_load(deps.storage)?})?),
+        QueryMsg::Timelock {} => Ok(to_binary(&TimelockResponse{timelock: TIMELOCK.may_load(deps.storage)?})?),
+        QueryMsg::QueuedCommand {id} => Ok(to_binary(&QUEUED_COMMANDS.load(deps.storage, U64Key::from(id))?)?),
+        QueryMsg::QueuedCommands {start_after, limit} => Ok(to_binary(&query_queued_commands(deps, start_after, limit)?)?),
+        QueryMsg::BeneficiaryPolicy {} => Ok(to_binary(&query_beneficiary_policy(deps, env)?)?),
+        QueryMsg::Beneficiaries {start_after, limit} => Ok(to_binary(&query_beneficiaries(deps, start_after, limit)?)?),
+        QueryMsg::ActionHistory {hot_wallet, start_after, limit} => Ok(to_binary(&query_action_history(deps, hot_wallet, start_after, limit)?)?),
+        QueryMsg::Reserves {} => Ok(to_binary(&ReservesResponse{reserves: load_reserves(deps.storage)?})?),
+        QueryMsg::Guardians {} => Ok(to_binary(&query_guardians(deps)?)?),
+        QueryMsg::RecoveryProposal {id} => Ok(to_binary(&RECOVERY_PROPOSALS.load(deps.storage, U64Key::from(id))?)?),
+        QueryMsg::RecoveryProposals {start_after, limit} => Ok(to_binary(&query_recovery_proposals(deps, start_after, limit)?)?),
+    }
+}
+
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
 
-    //gas_time_left is janky; it's the num seconds since last fillup
-    Ok(HotWalletStateResponse{
-        address: hot_wallet.address,
-        gas_time_left: max(env.block.time.seconds() - hot_wallet.last_gas_fillup, 0u64)
+    Ok(ConfigResponse {
+        cw3_address: config.cw3_address,
     })
   }
+  
+
+pub fn query_gas_tank_status(deps: Deps, env: Env, address: String) -> StdResult<GasTankStatusResponse> {
+    let hot_wallet_config: HotWallet = hot_wallet_configs().load(deps.storage, address.as_str())?;
+
+    //never filled up means no cooldown, same as FillUpGas
+    let cooldown_left = match HOT_WALLETS.may_load(deps.storage, address.clone())?{
+        Some(state) => (state.last_gas_fillup + hot_wallet_config.gas_cooldown).saturating_sub(env.block.time.seconds()),
+        None => 0u64,
+    };
+
+    let tanks: StdResult<Vec<GasTankStatus>> = hot_wallet_config.gas_tanks
+        .iter()
+        .map(|gas_tank| gas_tank_status(deps, env.contract.address.as_str(), address.as_str(), gas_tank))
+        .collect();
+
+    Ok(GasTankStatusResponse{
+        address,
+        cooldown_left,
+        tanks: tanks?,
+    })
+}
+
+pub fn query_hot_wallets(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<HotWalletsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let hot_wallets: StdResult<Vec<HotWallet>> = hot_wallet_configs()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, hot_wallet)| hot_wallet))
+        .collect();
+
+    Ok(HotWalletsResponse{hot_wallets: hot_wallets?})
+}
+
+pub fn query_hot_wallets_by_label(deps: Deps, label: String, start_after: Option<String>, limit: Option<u32>) -> StdResult<HotWalletsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let hot_wallets: StdResult<Vec<HotWallet>> = hot_wallet_configs()
+        .idx
+        .label
+        .prefix(label.as_bytes().to_vec())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, hot_wallet)| hot_wallet))
+        .collect();
+
+    Ok(HotWalletsResponse{hot_wallets: hot_wallets?})
+}
+
+pub fn query_hot_wallets_by_action(deps: Deps, action_id: u64, start_after: Option<String>, limit: Option<u32>) -> StdResult<HotWalletsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let hot_wallets: StdResult<Vec<HotWallet>> = hot_wallet_configs()
+        .idx
+        .action
+        .addresses(deps.storage, action_id, start_after, limit)?
+        .iter()
+        .map(|address| hot_wallet_configs().load(deps.storage, address.as_str()))
+        .collect();
+
+    Ok(HotWalletsResponse{hot_wallets: hot_wallets?})
+}
+
+pub fn query_whitelisted_contracts(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<WhitelistedContractsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let contracts: StdResult<Vec<WhitelistedContract>> = whitelisted_contracts()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, contract)| contract))
+        .collect();
+
+    Ok(WhitelistedContractsResponse{whitelisted_contracts: contracts?})
+}
+
+pub fn query_whitelist_drift(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<WhitelistDriftResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let contracts: StdResult<Vec<ContractDrift>> = whitelisted_contracts()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, contract)| contract_drift(&deps.querier, contract)))
+        .collect();
+
+    Ok(WhitelistDriftResponse{contracts: contracts?})
+}
+
+pub fn query_whitelisted_contracts_by_address(deps: Deps, address: String, start_after: Option<String>, limit: Option<u32>) -> StdResult<WhitelistedContractsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let contracts: StdResult<Vec<WhitelistedContract>> = whitelisted_contracts()
+        .idx
+        .address
+        .prefix(address.as_bytes().to_vec())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, contract)| contract))
+        .collect();
+
+    Ok(WhitelistedContractsResponse{whitelisted_contracts: contracts?})
+}
+
+pub fn query_action_template(deps: Deps, id: u64) -> StdResult<ActionTemplateResponse> {
+    let template: ActionTemplate = ACTION_TEMPLATES.load(deps.storage, U64Key::from(id))?;
+
+    Ok(ActionTemplateResponse{id, template})
+}
+
+pub fn query_action_templates(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<ActionTemplatesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|id| Bound::exclusive(U64Key::from(id)));
+
+    let templates: StdResult<Vec<ActionTemplateResponse>> = ACTION_TEMPLATES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, template) = item?;
+            let mut id_bytes = [0u8; 8];
+            id_bytes.copy_from_slice(&k);
+            Ok(ActionTemplateResponse{id: u64::from_be_bytes(id_bytes), template})
+        })
+        .collect();
+
+    Ok(ActionTemplatesResponse{templates: templates?})
+}
+
+pub fn query_spending_allowance(deps: Deps, env: Env, address: String) -> StdResult<SpendingAllowanceResponse> {
+    let hot_wallet: HotWallet = hot_wallet_configs().load(deps.storage, address.as_str())?;
+
+    Ok(SpendingAllowanceResponse{
+        allowances: remaining_allowances(deps.storage, &hot_wallet, env.block.time.seconds())?,
+        address,
+    })
+}
+
+pub fn query_action_state(deps: Deps, env: Env, address: String, action_id: u64) -> StdResult<ActionStateResponse> {
+    let hot_wallet: HotWallet = hot_wallet_configs().load(deps.storage, address.as_str())?;
+
+    let state = load_action_state(deps.storage, &address, action_id)?;
+
+    //no limit configured means the action is always allowed
+    let (next_allowed, period_calls) = match (hot_wallet.action_limits.iter().find(|&x| x.action_id == action_id), &state){
+        (Some(limit), Some(action_state)) if action_state.period_start + limit.period > env.block.time.seconds() => (
+            max(next_allowed_time(limit, state.as_ref()), env.block.time.seconds()),
+            action_state.period_calls,
+        ),
+        (Some(limit), _) => (max(next_allowed_time(limit, state.as_ref()), env.block.time.seconds()), 0u64),
+        (None, _) => (env.block.time.seconds(), 0u64),
+    };
+
+    Ok(ActionStateResponse{
+        address,
+        action_id,
+        last_execution: state.map(|x| x.last_execution),
+        period_calls,
+        next_allowed,
+    })
+}
+
+pub fn query_guardians(deps: Deps) -> StdResult<GuardiansResponse> {
+    let guardian_config: GuardianConfig = load_guardians(deps.storage)?;
+
+    Ok(GuardiansResponse{
+        guardians: guardian_config.guardians,
+        threshold: guardian_config.threshold,
+        delay: guardian_config.delay,
+    })
+}
+
+pub fn query_recovery_proposals(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<RecoveryProposalsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|id| Bound::exclusive(U64Key::from(id)));
+
+    let proposals: StdResult<Vec<RecoveryProposal>> = RECOVERY_PROPOSALS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, proposal)| proposal))
+        .collect();
+
+    Ok(RecoveryProposalsResponse{proposals: proposals?})
+}
+
+pub fn query_queued_commands(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<QueuedCommandsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|id| Bound::exclusive(U64Key::from(id)));
+
+    let commands: StdResult<Vec<QueuedCommand>> = QUEUED_COMMANDS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, queued)| queued))
+        .collect();
+
+    Ok(QueuedCommandsResponse{commands: commands?})
+}
+
+pub fn query_beneficiary_policy(deps: Deps, env: Env) -> StdResult<BeneficiaryPolicyResponse> {
+    let now = env.block.time.seconds();
+
+    Ok(BeneficiaryPolicyResponse{
+        policy: load_beneficiary_policy(deps.storage, now)?,
+        pending: PENDING_BENEFICIARY_POLICY.may_load(deps.storage)?.filter(|x| x.effective_at > now),
+    })
+}
+
+pub fn query_beneficiaries(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<BeneficiariesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let beneficiaries: StdResult<Vec<Beneficiary>> = BENEFICIARIES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (address, active_at) = item?;
+            Ok(Beneficiary{address: String::from_utf8(address).map_err(StdError::from)?, active_at})
+        })
+        .collect();
+
+    Ok(BeneficiariesResponse{beneficiaries: beneficiaries?})
+}
+
+pub fn query_action_history(deps: Deps, hot_wallet: Option<String>, start_after: Option<u64>, limit: Option<u32>) -> StdResult<ActionHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|id| Bound::exclusive(U64Key::from(id)));
+
+    let entries: StdResult<Vec<JournalEntry>> = match hot_wallet{
+        Some(hot_wallet) => journal()
+            .idx
+            .actor
+            .prefix(hot_wallet.as_bytes().to_vec())
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, entry)| entry))
+            .collect(),
+        None => journal()
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, entry)| entry))
+            .collect(),
+    };
+
+    Ok(ActionHistoryResponse{entries: entries?})
+}
+
+pub fn query_withdrawable(deps: Deps, env: Env) -> StdResult<WithdrawableUnbondedResponse> {
+    let bluna_hub_contract: WhitelistedContract = whitelisted_contracts().load(deps.storage, BLUNA_HUB_CONTRACT)?;
+
+    Ok(WithdrawableUnbondedResponse{
+        withdrawable: query_withdrawable_unbonded(&deps.querier, &bluna_hub_contract.address, env.contract.address.as_str())?,
+    })
+}
+
+pub fn query_unbond_requests_of_wallet(deps: Deps, env: Env) -> StdResult<UnbondRequestsResponse> {
+    let bluna_hub_contract: WhitelistedContract = whitelisted_contracts().load(deps.storage, BLUNA_HUB_CONTRACT)?;
+
+    Ok(UnbondRequestsResponse{
+        requests: query_unbond_requests(&deps.querier, &bluna_hub_contract.address, env.contract.address.as_str())?,
+    })
+}
+
+pub fn query_airdrops(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<AirdropsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let airdrops: StdResult<Vec<Airdrop>> = AIRDROPS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, airdrop)| airdrop))
+        .collect();
+
+    Ok(AirdropsResponse{airdrops: airdrops?})
+}
+
+pub fn query_swap_pairs(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<SwapPairsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let swap_pairs: StdResult<Vec<SwapPair>> = SWAP_PAIRS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, swap_pair)| swap_pair))
+        .collect();
+
+    Ok(SwapPairsResponse{swap_pairs: swap_pairs?})
+}
+
+pub fn query_cw20_balances(deps: Deps, env: Env, start_after: Option<String>, limit: Option<u32>) -> StdResult<Cw20BalancesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let tokens: Vec<String> = TRACKED_TOKENS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(String::from_utf8)
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(StdError::from)?;
+
+    let balances: StdResult<Vec<Cw20Balance>> = tokens.into_iter().map(|token| {
+        let balance = query_token_balance(deps, token.clone(), env.contract.address.to_string())?;
+        Ok(Cw20Balance{token, balance})
+    }).collect();
+
+    Ok(Cw20BalancesResponse{balances: balances?})
+}
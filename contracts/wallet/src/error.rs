@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 /// only purpose of this is to accommodate the execute command message.
@@ -30,6 +30,144 @@ pub enum ContractError {
   #[error("gas tank is full")]
   GasTankFull{},
 
+  #[error("invalid gas tanks: {reason}")]
+  InvalidGasTanks { reason: String },
+
+  #[error("invalid action limits: {reason}")]
+  InvalidActionLimits { reason: String },
+
+  #[error("invalid spending limits: {reason}")]
+  InvalidSpendingLimits { reason: String },
+
+  #[error("action template does not exist")]
+  ActionTemplateNotFound {},
+
+  #[error("invalid action template: {reason}")]
+  InvalidActionTemplate { reason: String },
+
+  #[error("invalid action param: {name}")]
+  InvalidActionParam { name: String },
+
+  #[error("action funds exceed template max")]
+  ActionFundsExceeded {},
+
+  #[error("spending limit exceeded for {denom}")]
+  SpendingLimitExceeded { denom: String },
+
+  #[error("action rate limited until {next_allowed}")]
+  ActionRateLimited { next_allowed: u64 },
+
+  #[error("cannot migrate from contract {stored}")]
+  MigrationWrongContract { stored: String },
+
+  #[error("cannot migrate from version {stored} to {current}")]
+  MigrationDowngrade { stored: String, current: String },
+
+  #[error("wallet is frozen")]
+  Frozen {},
+
+  #[error("wallet is not frozen")]
+  NotFrozen {},
+
+  #[error("invalid guardians: {reason}")]
+  InvalidGuardians { reason: String },
+
+  #[error("recovery proposal does not exist")]
+  RecoveryProposalNotFound {},
+
+  #[error("guardian already approved this recovery")]
+  RecoveryAlreadyApproved {},
+
+  #[error("recovery does not have enough guardian approvals")]
+  RecoveryNotApproved {},
+
+  #[error("recovery is in its veto window until {executable_at}")]
+  RecoveryDelayNotDone { executable_at: u64 },
+
+  #[error("loan config is not set")]
+  LoanConfigNotSet {},
+
+  #[error("invalid loan config: {reason}")]
+  InvalidLoanConfig { reason: String },
+
+  #[error("target ltv exceeded")]
+  TargetLtvExceeded {},
+
+  #[error("loan is already within the safe ltv")]
+  LoanWithinSafeLtv {},
+
+  #[error("not enough uusd and aust to repay {amount}")]
+  InsufficientRepayFunds { amount: String },
+
+  #[error("no bluna rewards to compound")]
+  NothingToCompound {},
+
+  #[error("airdrop does not exist")]
+  AirdropNotFound {},
+
+  #[error("no swap pair configured for airdrop")]
+  AirdropSwapNotConfigured {},
+
+  #[error("invalid airdrop: {reason}")]
+  InvalidAirdrop { reason: String },
+
+  #[error("swap pair does not exist")]
+  SwapPairNotFound {},
+
+  #[error("invalid swap pair: {reason}")]
+  InvalidSwapPair { reason: String },
+
+  #[error("invalid swap: {reason}")]
+  InvalidSwap { reason: String },
+
+  #[error("swap returned {received}, below the minimum of {min_receive}")]
+  SwapBelowMinimum { received: Uint128, min_receive: Uint128 },
+
+  #[error("token is not tracked")]
+  TokenNotTracked {},
+
+  #[error("validator is not approved for bonding")]
+  ValidatorNotApproved {},
+
+  #[error("invalid bond validators: {reason}")]
+  InvalidBondValidators { reason: String },
+
+  #[error("invalid timelock: {reason}")]
+  InvalidTimelock { reason: String },
+
+  #[error("queued command does not exist")]
+  QueuedCommandNotFound {},
+
+  #[error("queued command is timelocked until {eta}")]
+  TimelockNotDone { eta: u64 },
+
+  #[error("invalid beneficiary policy: {reason}")]
+  InvalidBeneficiaryPolicy { reason: String },
+
+  #[error("{address} is not an allowed beneficiary")]
+  BeneficiaryNotAllowed { address: String },
+
+  #[error("instantiating a contract with funds is not allowed while a beneficiary policy is in force")]
+  FundedInstantiate {},
+
+  #[error("beneficiary does not exist")]
+  BeneficiaryNotFound {},
+
+  #[error("whitelisted contract does not exist")]
+  WhitelistedContractNotFound {},
+
+  #[error("contract label {label} is already whitelisted")]
+  DuplicateContractLabel { label: String },
+
+  #[error("{address} runs code id {actual}, expected {expected}")]
+  CodeIdMismatch { address: String, expected: u64, actual: u64 },
+
+  #[error("invalid reserves: {reason}")]
+  InvalidReserves { reason: String },
+
+  #[error("action would take {denom} below its reserve of {reserve}")]
+  ReserveBreached { denom: String, reserve: Uint128 },
+
   #[error("reply id not implemented")]
   InvalidReplyId,
 
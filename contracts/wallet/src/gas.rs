@@ -0,0 +1,39 @@
+use cosmwasm_std::{Coin, Deps, StdResult};
+use basset::deduct_tax;
+use std::cmp::max;
+
+use smartwallet::wallet::{GasTank, GasTankStatus};
+use crate::tax_querier::query_balance;
+use crate::reserve::load_reserve;
+use crate::error::ContractError;
+
+pub fn validate_gas_tanks(gas_tanks: &[GasTank]) -> Result<(), ContractError> {
+    for (i, gas_tank) in gas_tanks.iter().enumerate(){
+        if gas_tanks[..i].iter().any(|x| x.denom == gas_tank.denom){
+            return Err(ContractError::InvalidGasTanks{reason: format!("duplicate gas tank for {}", gas_tank.denom)});
+        }
+    }
+    Ok(())
+}
+
+/// what topping up hot_wallet's gas_tank from wallet would send right now
+pub fn gas_tank_status(deps: Deps, wallet: &str, hot_wallet: &str, gas_tank: &GasTank) -> StdResult<GasTankStatus> {
+    let balance = query_balance(deps, hot_wallet.to_string(), gas_tank.denom.clone())?;
+    let deficit = gas_tank.max.saturating_sub(balance);
+
+    //tax comes out of the sent amount
+    let tax = deficit - deduct_tax(&deps.querier, Coin{denom: gas_tank.denom.clone(), amount: deficit})?.amount;
+
+    //the refill has to leave both the tank's buffer and the denom's reserve behind
+    let wallet_balance = query_balance(deps, wallet.to_string(), gas_tank.denom.clone())?;
+    let keep = max(gas_tank.buffer, load_reserve(deps.storage, &gas_tank.denom)?);
+
+    Ok(GasTankStatus{
+        denom: gas_tank.denom.clone(),
+        balance,
+        max: gas_tank.max,
+        deficit,
+        tax,
+        refill_funded: wallet_balance >= deficit + keep,
+    })
+}
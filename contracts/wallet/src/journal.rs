@@ -0,0 +1,41 @@
+use cosmwasm_std::{Addr, Env, Response, StdResult, Storage};
+use cw_storage_plus::U64Key;
+
+use smartwallet::wallet::{ActorRole, JournalEntry};
+use crate::state::{CONFIG, GUARDIANS, NEXT_JOURNAL_ID, hot_wallet_configs, journal};
+
+/// role of sender, if its actions go into the journal
+pub fn actor_role(storage: &dyn Storage, sender: &Addr) -> StdResult<Option<ActorRole>> {
+    if hot_wallet_configs().may_load(storage, sender.as_str())?.is_some(){
+        return Ok(Some(ActorRole::HotWallet));
+    }
+    if let Some(config) = CONFIG.may_load(storage)?{
+        if config.cw3_address == *sender{
+            return Ok(Some(ActorRole::Multisig));
+        }
+    }
+    match GUARDIANS.may_load(storage)?{
+        Some(guardian_config) if guardian_config.guardians.contains(sender) => Ok(Some(ActorRole::Guardian)),
+        _ => Ok(None),
+    }
+}
+
+/// appends res to the journal under the next id
+pub fn record_action(storage: &mut dyn Storage, env: &Env, actor: &Addr, role: ActorRole, res: &Response) -> StdResult<u64> {
+    let id = NEXT_JOURNAL_ID.may_load(storage)?.unwrap_or_default();
+
+    let action = res.attributes.iter().find(|x| x.key == "action").map(|x| x.value.clone()).unwrap_or_default();
+
+    journal().save(storage, U64Key::from(id), &JournalEntry{
+        id,
+        actor: actor.to_string(),
+        role,
+        action,
+        attributes: res.attributes.clone(),
+        height: env.block.height,
+        time: env.block.time.seconds(),
+    })?;
+    NEXT_JOURNAL_ID.save(storage, &(id + 1))?;
+
+    Ok(id)
+}
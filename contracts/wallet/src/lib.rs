@@ -1,5 +1,24 @@
+pub mod action;
+pub mod spending;
+pub mod gas;
+pub mod reserve;
+pub mod whitelist;
+pub mod rate_limit;
+pub mod migrate;
+pub mod recovery;
+pub mod timelock;
+pub mod beneficiary;
+pub mod journal;
+pub mod anchor;
+pub mod bluna;
+pub mod airdrop;
+pub mod swap;
 pub mod contract;
 pub mod state;
 pub mod tax_querier;
 pub mod error;
-pub mod response;
\ No newline at end of file
+//generated by protobuf codegen; newer toolchains flag its output
+#[allow(renamed_and_removed_lints, unused_parens, mismatched_lifetime_syntaxes)]
+pub mod response;
+#[cfg(test)]
+mod testing;
@@ -0,0 +1,167 @@
+use cosmwasm_std::{Addr, Coin, Decimal, Storage, StdError, StdResult, Order, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cw_storage_plus::{Item, Map};
+use smartwallet::wallet::{Airdrop, HotWallet, GasTank, SpendingLimit, ActionLimit, WhitelistedContract};
+use crate::state::{AIRDROPS, CONFIG, Config, hot_wallet_configs, whitelisted_contracts};
+use crate::contract::DEFAULT_UUSD_RESERVE;
+use crate::reserve::save_reserves;
+
+/// hot wallet layout up to 0.4.x, with a single uusd gas tank
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HotWalletV0_4 {
+    pub address: String,
+    pub label: String,
+    pub gas_cooldown: u64,
+    pub gas_tank_max: Uint128,
+    pub whitelisted_messages: Vec<u64>,
+    #[serde(default)]
+    pub spending_limits: Vec<SpendingLimit>,
+    #[serde(default)]
+    pub action_limits: Vec<ActionLimit>,
+}
+
+impl From<HotWalletV0_4> for HotWallet {
+    fn from(legacy: HotWalletV0_4) -> Self {
+        HotWallet{
+            address: legacy.address,
+            label: legacy.label,
+            gas_cooldown: legacy.gas_cooldown,
+            //the old 100 UST buffer becomes the uusd reserve in migrate_reserves, which always runs after this
+            gas_tanks: vec![GasTank{
+                denom: String::from("uusd"),
+                max: legacy.gas_tank_max,
+                buffer: Uint128::zero(),
+            }],
+            whitelisted_messages: legacy.whitelisted_messages,
+            spending_limits: legacy.spending_limits,
+            action_limits: legacy.action_limits,
+        }
+    }
+}
+
+/// config layout up to 0.2.0, hot wallets were kept inline
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigV0_2 {
+    pub hot_wallets: Vec<HotWalletV0_4>,
+    pub cw3_address: Addr,
+    pub whitelisted_contracts: Vec<WhitelistedContract>,
+}
+
+/// config layout in 0.3.x, whitelisted contracts were still kept inline
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigV0_3 {
+    pub cw3_address: Addr,
+    pub whitelisted_contracts: Vec<WhitelistedContract>,
+}
+
+/// airdrop layout up to 0.5.x, contracts were kept as raw addresses
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AirdropV0_5 {
+    pub label: String,
+    pub airdrop_contract: String,
+    pub token_contract: String,
+    pub pair_contract: Option<String>,
+    pub max_spread: Decimal,
+}
+
+pub const CONFIG_V0_2: Item<ConfigV0_2> = Item::new("\u{0}\u{6}config");
+pub const CONFIG_V0_3: Item<ConfigV0_3> = Item::new("\u{0}\u{6}config");
+
+/// 0.3.x kept hot wallets in a plain map under the same namespace as the indexed one;
+/// through 0.4.x the entries keep the old layout
+pub const HOT_WALLET_CONFIGS_V0_4: Map<&str, HotWalletV0_4> = Map::new("hotwalletconfigs");
+
+pub const AIRDROPS_V0_5: Map<&str, AirdropV0_5> = Map::new("airdrops");
+
+/// 0.2.0 -> 0.3.0: moves Config.hot_wallets into a map keyed by address
+pub fn migrate_hot_wallets_to_map(storage: &mut dyn Storage) -> StdResult<()> {
+    let legacy_config: ConfigV0_2 = CONFIG_V0_2.load(storage)?;
+
+    for hot_wallet in legacy_config.hot_wallets.iter(){
+        HOT_WALLET_CONFIGS_V0_4.save(storage, hot_wallet.address.as_str(), hot_wallet)?;
+    }
+
+    CONFIG_V0_3.save(storage, &ConfigV0_3{
+        cw3_address: legacy_config.cw3_address,
+        whitelisted_contracts: legacy_config.whitelisted_contracts,
+    })
+}
+
+/// 0.3.x -> 0.4.0: moves Config.whitelisted_contracts into a map keyed by label.
+/// the hot wallet secondary indexes are built by migrate_gas_tanks
+pub fn migrate_to_indexed_maps(storage: &mut dyn Storage) -> StdResult<()> {
+    let legacy_config: ConfigV0_3 = CONFIG_V0_3.load(storage)?;
+
+    for contract in legacy_config.whitelisted_contracts.iter(){
+        whitelisted_contracts().save(storage, contract.label.as_str(), contract)?;
+    }
+
+    CONFIG.save(storage, &Config{
+        cw3_address: legacy_config.cw3_address,
+    })
+}
+
+/// 0.4.x -> 0.5.0: turns gas_tank_max into a uusd gas tank without a buffer of its own,
+/// then saves every hot wallet through the indexed map to (re)build its indexes
+pub fn migrate_gas_tanks(storage: &mut dyn Storage) -> StdResult<()> {
+    let hot_wallets: Vec<HotWalletV0_4> = HOT_WALLET_CONFIGS_V0_4
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, hot_wallet)| hot_wallet))
+        .collect::<StdResult<Vec<HotWalletV0_4>>>()?;
+
+    for hot_wallet in hot_wallets.into_iter(){
+        //the indexed map would read the old entry in the new layout, so it goes first
+        HOT_WALLET_CONFIGS_V0_4.remove(storage, hot_wallet.address.as_str());
+
+        let hot_wallet: HotWallet = hot_wallet.into();
+        hot_wallet_configs().save(storage, hot_wallet.address.as_str(), &hot_wallet)?;
+    }
+
+    Ok(())
+}
+
+/// 0.5.x -> 0.6.0: the 100 UST every hot msg used to leave behind becomes the uusd reserve
+pub fn migrate_reserves(storage: &mut dyn Storage) -> StdResult<()> {
+    save_reserves(storage, &[Coin{denom: String::from("uusd"), amount: Uint128::from(DEFAULT_UUSD_RESERVE)}])
+}
+
+/// 0.5.x -> 0.6.0: airdrops reference their contracts by whitelisted label. fails if a contract
+/// isn't whitelisted, so the multisig whitelists it or removes the airdrop before migrating
+pub fn migrate_airdrop_labels(storage: &mut dyn Storage) -> StdResult<()> {
+    let airdrops: Vec<AirdropV0_5> = AIRDROPS_V0_5
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, airdrop)| airdrop))
+        .collect::<StdResult<Vec<AirdropV0_5>>>()?;
+
+    for airdrop in airdrops.into_iter(){
+        let pair_label = match &airdrop.pair_contract{
+            Some(pair_contract) => Some(whitelisted_label(storage, &airdrop.label, pair_contract)?),
+            None => None,
+        };
+        let migrated = Airdrop{
+            airdrop_label: whitelisted_label(storage, &airdrop.label, &airdrop.airdrop_contract)?,
+            token_label: whitelisted_label(storage, &airdrop.label, &airdrop.token_contract)?,
+            pair_label,
+            max_spread: airdrop.max_spread,
+            label: airdrop.label,
+        };
+
+        AIRDROPS.save(storage, migrated.label.as_str(), &migrated)?;
+    }
+
+    Ok(())
+}
+
+fn whitelisted_label(storage: &dyn Storage, airdrop: &str, address: &str) -> StdResult<String> {
+    whitelisted_contracts()
+        .idx
+        .address
+        .prefix(address.as_bytes().to_vec())
+        .range(storage, None, None, Order::Ascending)
+        .next()
+        .transpose()?
+        .map(|(_, contract)| contract.label)
+        .ok_or_else(|| StdError::generic_err(format!("airdrop {} uses {}, which is not whitelisted", airdrop, address)))
+}
@@ -0,0 +1,79 @@
+use cosmwasm_std::{Storage, StdResult};
+use cw_storage_plus::U64Key;
+use std::cmp::max;
+
+use smartwallet::wallet::{HotWallet, ActionLimit};
+use crate::state::{HOT_WALLET_ACTIONS, HotWalletActionState};
+use crate::error::ContractError;
+
+pub fn validate_action_limits(action_limits: &[ActionLimit]) -> Result<(), ContractError> {
+    for (i, action_limit) in action_limits.iter().enumerate(){
+        if action_limits[..i].iter().any(|x| x.action_id == action_limit.action_id){
+            return Err(ContractError::InvalidActionLimits{reason: format!("duplicate limit for action {}", action_limit.action_id)});
+        }
+        //a zero period would never count calls
+        if action_limit.max_calls.is_some() && action_limit.period == 0{
+            return Err(ContractError::InvalidActionLimits{reason: format!("max_calls for action {} needs a non-zero period", action_limit.action_id)});
+        }
+    }
+    Ok(())
+}
+
+/// checks the hot wallet's ActionLimit for action_id (if any) and records the execution
+pub fn consume_action_limit(
+    storage: &mut dyn Storage,
+    hot_wallet: &HotWallet,
+    action_id: u64,
+    now: u64,
+) -> Result<(), ContractError> {
+
+    let limit = match hot_wallet.action_limits.iter().find(|&x| x.action_id == action_id){
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+
+    let state = HOT_WALLET_ACTIONS.may_load(storage, (hot_wallet.address.as_str(), U64Key::from(action_id)))?;
+
+    let next_allowed = next_allowed_time(limit, state.as_ref());
+    if next_allowed > now{
+        return Err(ContractError::ActionRateLimited{next_allowed});
+    }
+
+    let state = match state{
+        //still inside the current period
+        Some(state) if state.period_start + limit.period > now => HotWalletActionState{
+            action_id,
+            last_execution: now,
+            period_start: state.period_start,
+            period_calls: state.period_calls + 1,
+        },
+        _ => HotWalletActionState{
+            action_id,
+            last_execution: now,
+            period_start: now,
+            period_calls: 1,
+        },
+    };
+
+    HOT_WALLET_ACTIONS.save(storage, (hot_wallet.address.as_str(), U64Key::from(action_id)), &state)?;
+
+    Ok(())
+}
+
+pub fn next_allowed_time(limit: &ActionLimit, state: Option<&HotWalletActionState>) -> u64 {
+    let state = match state{
+        Some(state) => state,
+        None => return 0u64,
+    };
+
+    let cooldown_done = state.last_execution + limit.cooldown;
+
+    match limit.max_calls{
+        Some(max_calls) if state.period_calls >= max_calls => max(cooldown_done, state.period_start + limit.period),
+        _ => cooldown_done,
+    }
+}
+
+pub fn load_action_state(storage: &dyn Storage, address: &str, action_id: u64) -> StdResult<Option<HotWalletActionState>> {
+    HOT_WALLET_ACTIONS.may_load(storage, (address, U64Key::from(action_id)))
+}
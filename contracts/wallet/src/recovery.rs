@@ -0,0 +1,72 @@
+use cosmwasm_std::{Addr, Api, Storage, StdResult, Order};
+use cw_storage_plus::U64Key;
+
+use crate::state::{CONFIG, Config, GuardianConfig, RECOVERY_PROPOSALS, QUEUED_COMMANDS};
+use crate::error::ContractError;
+
+/// an empty guardian list disables recovery; otherwise threshold must be reachable and delay non-zero
+pub fn validate_guardians(
+    api: &dyn Api,
+    guardians: &[String],
+    threshold: u64,
+    delay: u64,
+) -> Result<GuardianConfig, ContractError> {
+
+    let mut validated: Vec<Addr> = vec![];
+    for guardian in guardians.iter(){
+        let addr = api.addr_validate(guardian)?;
+        if validated.contains(&addr){
+            return Err(ContractError::InvalidGuardians{reason: format!("duplicate guardian {}", guardian)});
+        }
+        validated.push(addr);
+    }
+
+    if !validated.is_empty(){
+        if threshold == 0 || threshold > validated.len() as u64{
+            return Err(ContractError::InvalidGuardians{reason: String::from("threshold must be between 1 and the number of guardians")});
+        }
+
+        //the delay is the multisig's only chance to veto
+        if delay == 0{
+            return Err(ContractError::InvalidGuardians{reason: String::from("delay must be non-zero")});
+        }
+    }
+
+    Ok(GuardianConfig{
+        guardians: validated,
+        threshold,
+        delay,
+    })
+}
+
+/// swaps the multisig and drops pending recoveries, which were aimed at the old one, and the
+/// old one's queued commands, which would otherwise still run once their eta passes
+pub fn replace_multisig(storage: &mut dyn Storage, cw3_address: Addr) -> StdResult<()> {
+    CONFIG.save(storage, &Config{cw3_address})?;
+    clear_recovery_proposals(storage)?;
+    clear_queued_commands(storage)
+}
+
+pub fn clear_recovery_proposals(storage: &mut dyn Storage) -> StdResult<()> {
+    let ids: Vec<Vec<u8>> = RECOVERY_PROPOSALS
+        .keys(storage, None, None, Order::Ascending)
+        .collect();
+
+    for id in ids.iter(){
+        RECOVERY_PROPOSALS.remove(storage, U64Key::from(id.clone()));
+    }
+
+    Ok(())
+}
+
+pub fn clear_queued_commands(storage: &mut dyn Storage) -> StdResult<()> {
+    let ids: Vec<Vec<u8>> = QUEUED_COMMANDS
+        .keys(storage, None, None, Order::Ascending)
+        .collect();
+
+    for id in ids.iter(){
+        QUEUED_COMMANDS.remove(storage, U64Key::from(id.clone()));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use cosmwasm_std::{Coin, Deps, Order, StdError, StdResult, Storage, Uint128};
+
+use crate::state::RESERVES;
+use crate::tax_querier::{query_balance, query_token_balance};
+use crate::error::ContractError;
+
+pub fn validate_reserves(reserves: &[Coin]) -> Result<(), ContractError> {
+    for (i, reserve) in reserves.iter().enumerate(){
+        if reserves[..i].iter().any(|x| x.denom == reserve.denom){
+            return Err(ContractError::InvalidReserves{reason: format!("duplicate reserve for {}", reserve.denom)});
+        }
+    }
+    Ok(())
+}
+
+/// replaces every reserve; zero amounts are dropped
+pub fn save_reserves(storage: &mut dyn Storage, reserves: &[Coin]) -> StdResult<()> {
+    for reserve in load_reserves(storage)?.iter(){
+        RESERVES.remove(storage, reserve.denom.as_str());
+    }
+    for reserve in reserves.iter().filter(|x| !x.amount.is_zero()){
+        RESERVES.save(storage, reserve.denom.as_str(), &reserve.amount)?;
+    }
+    Ok(())
+}
+
+pub fn load_reserves(storage: &dyn Storage) -> StdResult<Vec<Coin>> {
+    RESERVES
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin{denom: String::from_utf8(denom).map_err(StdError::invalid_utf8)?, amount})
+        })
+        .collect()
+}
+
+pub fn load_reserve(storage: &dyn Storage, denom: &str) -> StdResult<Uint128> {
+    Ok(RESERVES.may_load(storage, denom)?.unwrap_or_default())
+}
+
+/// part of balance a hot msg may spend
+pub fn spendable(storage: &dyn Storage, denom: &str, balance: Uint128) -> StdResult<Uint128> {
+    Ok(balance.saturating_sub(load_reserve(storage, denom)?))
+}
+
+/// errors if spending amount out of balance leaves less than the reserve
+pub fn assert_reserve(storage: &dyn Storage, denom: &str, balance: Uint128, amount: Uint128) -> Result<(), ContractError> {
+    let reserve = load_reserve(storage, denom)?;
+    //an amount that overflows can't leave the reserve either
+    if amount.checked_add(reserve).map_or(true, |x| balance < x){
+        return Err(ContractError::ReserveBreached{denom: denom.to_string(), reserve});
+    }
+    Ok(())
+}
+
+/// amount capped at what balance can spare; errors if the reserve leaves nothing to spend
+pub fn cap_to_reserve(storage: &dyn Storage, denom: &str, balance: Uint128, amount: Uint128) -> Result<Uint128, ContractError> {
+    let reserve = load_reserve(storage, denom)?;
+    if !reserve.is_zero() && balance <= reserve{
+        return Err(ContractError::ReserveBreached{denom: denom.to_string(), reserve});
+    }
+    Ok(std::cmp::min(balance.saturating_sub(reserve), amount))
+}
+
+/// native coins sent out of wallet, checked against their reserves
+pub fn assert_coin_reserves(deps: Deps, wallet: &str, coins: &[Coin]) -> Result<(), ContractError> {
+    for coin in coins.iter(){
+        let balance = query_balance(deps, wallet.to_string(), coin.denom.clone())?;
+        assert_reserve(deps.storage, &coin.denom, balance, coin.amount)?;
+    }
+    Ok(())
+}
+
+/// amount of token sent out of wallet; the balance is only queried when token has a reserve
+pub fn assert_token_reserve(deps: Deps, wallet: &str, token: &str, amount: Uint128) -> Result<(), ContractError> {
+    if load_reserve(deps.storage, token)?.is_zero(){
+        return Ok(());
+    }
+    let balance = query_token_balance(deps, token.to_string(), wallet.to_string())?;
+    assert_reserve(deps.storage, token, balance, amount)
+}
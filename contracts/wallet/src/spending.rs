@@ -0,0 +1,89 @@
+use cosmwasm_std::{Storage, Coin, Uint128, StdResult};
+
+use smartwallet::wallet::{HotWallet, SpendingAllowance, SpendingLimit};
+use crate::state::{HOT_WALLET_SPENDS, Spend};
+use crate::error::ContractError;
+
+/// a denom may have several limits, one per window
+pub fn validate_spending_limits(spending_limits: &[SpendingLimit]) -> Result<(), ContractError> {
+    for (i, spending_limit) in spending_limits.iter().enumerate(){
+        if spending_limit.window == 0{
+            return Err(ContractError::InvalidSpendingLimits{reason: format!("window for {} must be non-zero", spending_limit.denom)});
+        }
+        if spending_limits[..i].iter().any(|x| x.denom == spending_limit.denom && x.window == spending_limit.window){
+            return Err(ContractError::InvalidSpendingLimits{reason: format!("duplicate {}s limit for {}", spending_limit.window, spending_limit.denom)});
+        }
+    }
+    Ok(())
+}
+
+/// checks coins against every spending limit of the hot wallet and records them.
+/// the spend log for a denom only keeps entries inside its longest window
+pub fn consume_spending_limits(
+    storage: &mut dyn Storage,
+    hot_wallet: &HotWallet,
+    coins: &[Coin],
+    now: u64,
+) -> Result<(), ContractError> {
+
+    for coin in coins.iter().filter(|x| !x.amount.is_zero()){
+        let limits: Vec<_> = hot_wallet.spending_limits.iter().filter(|x| x.denom == coin.denom).collect();
+
+        //no limit configured for this denom
+        if limits.is_empty(){
+            continue;
+        }
+
+        let longest_window = limits.iter().map(|x| x.window).max().unwrap_or_default();
+
+        let mut spends: Vec<Spend> = HOT_WALLET_SPENDS
+            .may_load(storage, (hot_wallet.address.as_str(), coin.denom.as_str()))?
+            .unwrap_or_default();
+
+        spends.retain(|x| x.time.saturating_add(longest_window) > now);
+
+        for limit in limits.iter(){
+            let spent = spent_in_window(&spends, limit.window, now);
+
+            //an amount that overflows is over any limit
+            if spent.checked_add(coin.amount).map_or(true, |x| x > limit.max_amount){
+                return Err(ContractError::SpendingLimitExceeded{denom: coin.denom.clone()});
+            }
+        }
+
+        spends.push(Spend{time: now, amount: coin.amount});
+
+        HOT_WALLET_SPENDS.save(storage, (hot_wallet.address.as_str(), coin.denom.as_str()), &spends)?;
+    }
+
+    Ok(())
+}
+
+pub fn remaining_allowances(
+    storage: &dyn Storage,
+    hot_wallet: &HotWallet,
+    now: u64,
+) -> StdResult<Vec<SpendingAllowance>> {
+
+    hot_wallet.spending_limits.iter().map(|limit| {
+        let spends: Vec<Spend> = HOT_WALLET_SPENDS
+            .may_load(storage, (hot_wallet.address.as_str(), limit.denom.as_str()))?
+            .unwrap_or_default();
+
+        let spent = spent_in_window(&spends, limit.window, now);
+
+        Ok(SpendingAllowance{
+            denom: limit.denom.clone(),
+            window: limit.window,
+            max_amount: limit.max_amount,
+            spent,
+            remaining: limit.max_amount.saturating_sub(spent),
+        })
+    }).collect()
+}
+
+fn spent_in_window(spends: &[Spend], window: u64, now: u64) -> Uint128 {
+    spends.iter()
+        .filter(|x| x.time.saturating_add(window) > now)
+        .fold(Uint128::zero(), |acc, x| acc.saturating_add(x.amount))
+}
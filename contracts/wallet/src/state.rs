@@ -1,18 +1,146 @@
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Uint128, Storage, StdResult, StdError, Order, Empty};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cw_storage_plus::{Map, Item};
-use smartwallet::wallet::{HotWallet, WhitelistedContract};
+use cw_storage_plus::{Map, Item, U64Key, IndexedMap, MultiIndex, Index, IndexList, Bound};
+use smartwallet::wallet::{HotWallet, WhitelistedContract, ActionTemplate, Airdrop, SwapPair, AssetInfo, RecoveryProposal, FreezeInfo, TimelockConfig, QueuedCommand, LoanConfig, BeneficiaryPolicy, PendingBeneficiaryPolicy, JournalEntry};
 
 pub const HOT_WALLETS: Map<String, HotWalletState> = Map::new("hotwallets");
 pub const CONFIG: Item<Config> = Item::new("\u{0}\u{6}config");
+pub const ACTION_TEMPLATES: Map<U64Key, ActionTemplate> = Map::new("actiontemplates");
+pub const NEXT_ACTION_TEMPLATE_ID: Item<u64> = Item::new("nextactiontemplateid");
+pub const HOT_WALLET_SPENDS: Map<(&str, &str), Vec<Spend>> = Map::new("hotwalletspends");
+pub const HOT_WALLET_ACTIONS: Map<(&str, U64Key), HotWalletActionState> = Map::new("hotwalletactions");
+pub const GUARDIANS: Item<GuardianConfig> = Item::new("guardians");
+pub const RECOVERY_PROPOSALS: Map<U64Key, RecoveryProposal> = Map::new("recoveryproposals");
+pub const NEXT_RECOVERY_PROPOSAL_ID: Item<u64> = Item::new("nextrecoveryproposalid");
+pub const FROZEN: Item<FreezeInfo> = Item::new("frozen");
+pub const LOAN_CONFIG: Item<LoanConfig> = Item::new("loanconfig");
+pub const BOND_VALIDATORS: Item<Vec<String>> = Item::new("bondvalidators");
+pub const AIRDROPS: Map<&str, Airdrop> = Map::new("airdrops");
+pub const SWAP_PAIRS: Map<&str, SwapPair> = Map::new("swappairs");
+pub const TRACKED_TOKENS: Map<&str, Empty> = Map::new("trackedtokens");
+pub const PENDING_SWAP: Item<PendingSwap> = Item::new("pendingswap");
+pub const PENDING_COMPOUND: Item<PendingCompound> = Item::new("pendingcompound");
+pub const TIMELOCK: Item<TimelockConfig> = Item::new("timelock");
+pub const QUEUED_COMMANDS: Map<U64Key, QueuedCommand> = Map::new("queuedcommands");
+pub const NEXT_QUEUED_COMMAND_ID: Item<u64> = Item::new("nextqueuedcommandid");
+pub const BENEFICIARY_POLICY: Item<BeneficiaryPolicy> = Item::new("beneficiarypolicy");
+pub const PENDING_BENEFICIARY_POLICY: Item<PendingBeneficiaryPolicy> = Item::new("pendingbeneficiarypolicy");
+pub const BENEFICIARIES: Map<&str, u64> = Map::new("beneficiaries"); //address -> active_at
+pub const WHITELIST_ACTIVE_AT: Map<&str, u64> = Map::new("whitelistactiveat"); //whitelisted contract address -> active_at as a recipient
+pub const NEXT_JOURNAL_ID: Item<u64> = Item::new("nextjournalid");
+pub const RESERVES: Map<&str, Uint128> = Map::new("reserves"); //denom or cw20 address -> minimum balance
+
+/// append-only journal keyed by id
+pub fn journal<'a>() -> IndexedMap<'a, U64Key, JournalEntry, JournalIndexes<'a>> {
+    let indexes = JournalIndexes {
+        actor: MultiIndex::new(|d, k| (d.actor.as_bytes().to_vec(), k), "journal", "journal__actor"),
+    };
+    IndexedMap::new("journal", indexes)
+}
+
+pub struct JournalIndexes<'a> {
+    pub actor: MultiIndex<'a, (Vec<u8>, Vec<u8>), JournalEntry>,
+}
+
+impl<'a> IndexList<JournalEntry> for JournalIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<JournalEntry>> + '_> {
+        let v: Vec<&dyn Index<JournalEntry>> = vec![&self.actor];
+        Box::new(v.into_iter())
+    }
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
-    pub hot_wallets: Vec<HotWallet>,
     pub cw3_address: Addr,
-    pub whitelisted_contracts: Vec<WhitelistedContract>,
+}
+
+/// guardians able to replace cw3_address; delay (seconds) is the veto window after threshold approvals
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GuardianConfig {
+    pub guardians: Vec<Addr>,
+    pub threshold: u64,
+    pub delay: u64,
+}
+
+/// hot wallet configs keyed by address
+pub fn hot_wallet_configs<'a>() -> IndexedMap<'a, &'a str, HotWallet, HotWalletIndexes<'a>> {
+    let indexes = HotWalletIndexes {
+        label: MultiIndex::new(|d, k| (d.label.as_bytes().to_vec(), k), "hotwalletconfigs", "hotwalletconfigs__label"),
+        action: ActionIndex::new("hotwalletconfigs__action"),
+    };
+    IndexedMap::new("hotwalletconfigs", indexes)
+}
+
+pub struct HotWalletIndexes<'a> {
+    pub label: MultiIndex<'a, (Vec<u8>, Vec<u8>), HotWallet>,
+    pub action: ActionIndex<'a>,
+}
+
+impl<'a> IndexList<HotWallet> for HotWalletIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<HotWallet>> + '_> {
+        let v: Vec<&dyn Index<HotWallet>> = vec![&self.label, &self.action];
+        Box::new(v.into_iter())
+    }
+}
+
+/// indexes a hot wallet once per entry of whitelisted_messages, which MultiIndex can't do
+pub struct ActionIndex<'a> {
+    idx_map: Map<'a, (U64Key, Vec<u8>), bool>,
+}
+
+impl<'a> ActionIndex<'a> {
+    pub const fn new(idx_namespace: &'a str) -> Self {
+        ActionIndex { idx_map: Map::new(idx_namespace) }
+    }
+
+    /// addresses of hot wallets allowed to run action_id, ascending
+    pub fn addresses(&self, store: &dyn Storage, action_id: u64, start_after: Option<String>, limit: usize) -> StdResult<Vec<String>> {
+        let start = start_after.map(Bound::exclusive);
+
+        self.idx_map
+            .prefix(U64Key::from(action_id))
+            .keys(store, start, None, Order::Ascending)
+            .take(limit)
+            .map(|k| String::from_utf8(k).map_err(StdError::invalid_utf8))
+            .collect()
+    }
+}
+
+impl<'a> Index<HotWallet> for ActionIndex<'a> {
+    fn save(&self, store: &mut dyn Storage, pk: &[u8], data: &HotWallet) -> StdResult<()> {
+        for action_id in data.whitelisted_messages.iter(){
+            self.idx_map.save(store, (U64Key::from(*action_id), pk.to_vec()), &true)?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, store: &mut dyn Storage, pk: &[u8], old_data: &HotWallet) -> StdResult<()> {
+        for action_id in old_data.whitelisted_messages.iter(){
+            self.idx_map.remove(store, (U64Key::from(*action_id), pk.to_vec()));
+        }
+        Ok(())
+    }
+}
+
+/// whitelisted contracts keyed by label
+pub fn whitelisted_contracts<'a>() -> IndexedMap<'a, &'a str, WhitelistedContract, WhitelistedContractIndexes<'a>> {
+    let indexes = WhitelistedContractIndexes {
+        address: MultiIndex::new(|d, k| (d.address.as_bytes().to_vec(), k), "whitelistedcontracts", "whitelistedcontracts__address"),
+    };
+    IndexedMap::new("whitelistedcontracts", indexes)
+}
+
+pub struct WhitelistedContractIndexes<'a> {
+    pub address: MultiIndex<'a, (Vec<u8>, Vec<u8>), WhitelistedContract>,
+}
+
+impl<'a> IndexList<WhitelistedContract> for WhitelistedContractIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<WhitelistedContract>> + '_> {
+        let v: Vec<&dyn Index<WhitelistedContract>> = vec![&self.address];
+        Box::new(v.into_iter())
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -21,9 +149,34 @@ pub struct HotWalletState {
     pub last_gas_fillup: u64,
 }
 
-// unused
+/// a BlunaCompound waiting on its claim reply; the uusd balance before the claim tells what arrived
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingCompound {
+    pub hot_wallet: String,
+    pub balance_before: Uint128,
+}
+
+/// a Swap waiting on its reply; the ask balance before the swap tells what arrived
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingSwap {
+    pub hot_wallet: String,
+    pub ask_asset: AssetInfo,
+    pub balance_before: Uint128,
+    pub min_receive: Uint128,
+}
+
+/// funds moved out by a hot action; kept per (hot wallet, denom) for the rolling spending windows
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Spend {
+    pub time: u64,
+    pub amount: Uint128,
+}
+
+/// per (hot wallet, action id) execution tracking for ActionLimit
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct HotWalletActionState {
     pub action_id: u64,
     pub last_execution: u64,
+    pub period_start: u64,
+    pub period_calls: u64,
 }
\ No newline at end of file
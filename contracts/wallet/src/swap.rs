@@ -0,0 +1,73 @@
+use cosmwasm_std::{Decimal, Deps, Fraction, StdResult, Uint128, Uint256};
+use std::convert::TryFrom;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use smartwallet::wallet::{AssetInfo, SwapPair};
+use crate::tax_querier::{query_balance, query_token_balance};
+use crate::error::ContractError;
+
+/// terraswap pair msgs. the terraswap crate resolves to a cosmwasm-std 1.0 build,
+/// so the wire format is kept here against our cosmwasm-std
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PairExecuteMsg {
+    Swap {
+        offer_asset: Asset,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PairCw20HookMsg {
+    Swap {
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Asset {
+    pub info: AssetInfo,
+    pub amount: Uint128,
+}
+
+pub fn validate_swap_pair(swap_pair: &SwapPair) -> Result<(), ContractError> {
+    if swap_pair.offer_asset == swap_pair.ask_asset{
+        return Err(ContractError::InvalidSwapPair{reason: String::from("offer and ask asset are the same")});
+    }
+    if swap_pair.max_spread >= Decimal::one(){
+        return Err(ContractError::InvalidSwapPair{reason: String::from("max_spread must be below 1")});
+    }
+    if swap_pair.min_belief_price.is_zero() || swap_pair.min_belief_price > swap_pair.max_belief_price{
+        return Err(ContractError::InvalidSwapPair{reason: String::from("belief price range must be non-zero and ordered")});
+    }
+    Ok(())
+}
+
+/// least the pair may pay out for offer_amount: offer_amount / belief_price * (1 - max_spread).
+/// saturates, so an amount that can't be paid out just makes the swap revert
+pub fn min_swap_return(offer_amount: Uint128, belief_price: Decimal, max_spread: Decimal) -> Uint128 {
+    let after_spread = offer_amount * (Decimal::one() - max_spread);
+    let min_return = after_spread.full_mul(belief_price.denominator()) / Uint256::from(belief_price.numerator());
+    Uint128::try_from(min_return).unwrap_or(Uint128::MAX)
+}
+
+/// the key spending limits use for the asset: native denom or cw20 address
+pub fn asset_denom(asset: &AssetInfo) -> String {
+    match asset{
+        AssetInfo::NativeToken{denom} => denom.clone(),
+        AssetInfo::Token{contract_addr} => contract_addr.clone(),
+    }
+}
+
+pub fn query_asset_balance(deps: Deps, asset: &AssetInfo, account_addr: String) -> StdResult<Uint128> {
+    match asset{
+        AssetInfo::NativeToken{denom} => query_balance(deps, account_addr, denom.clone()),
+        AssetInfo::Token{contract_addr} => query_token_balance(deps, contract_addr.clone(), account_addr),
+    }
+}
@@ -1,31 +1,5 @@
-use cosmwasm_std::{Coin, Decimal, QuerierWrapper, StdResult, Uint128, Deps, BalanceResponse, QueryRequest, BankQuery};
-
-use terra_cosmwasm::TerraQuerier;
-
-
-
-static DECIMAL_FRACTION: Uint128 = Uint128::new(1_000_000_000_000_000_000u128);
-
-pub fn compute_tax(querier: &QuerierWrapper, coin: &Coin) -> StdResult<Uint128> {
-    let terra_querier = TerraQuerier::new(querier);
-    let tax_rate: Decimal = (terra_querier.query_tax_rate()?).rate;
-    let tax_cap: Uint128 = (terra_querier.query_tax_cap(coin.denom.to_string())?).cap;
-    Ok(std::cmp::min(
-        (coin.amount.checked_sub(coin.amount.multiply_ratio(
-            DECIMAL_FRACTION,
-            DECIMAL_FRACTION * tax_rate + DECIMAL_FRACTION,
-        )))?,
-        tax_cap,
-    ))
-}
-
-pub fn deduct_tax(querier: &QuerierWrapper, coin: Coin) -> StdResult<Coin> {
-    let tax_amount = compute_tax(querier, &coin)?;
-    Ok(Coin {
-        denom: coin.denom,
-        amount: (coin.amount.checked_sub(tax_amount))?,
-    })
-}
+use cosmwasm_std::{StdResult, Uint128, Deps, BalanceResponse, QueryRequest, BankQuery, WasmQuery, to_binary};
+use cw20::{Cw20QueryMsg, BalanceResponse as Cw20BalanceResponse};
 
 pub fn query_balance(deps: Deps, account_addr: String, denom: String) -> StdResult<Uint128> {
     // load price form the oracle
@@ -33,5 +7,13 @@ pub fn query_balance(deps: Deps, account_addr: String, denom: String) -> StdResu
         address: account_addr,
         denom,
     }))?;
-    Ok(balance.amount.amount.into())
-}
\ No newline at end of file
+    Ok(balance.amount.amount)
+}
+
+pub fn query_token_balance(deps: Deps, token_addr: String, account_addr: String) -> StdResult<Uint128> {
+    let balance: Cw20BalanceResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: token_addr,
+        msg: to_binary(&Cw20QueryMsg::Balance{address: account_addr})?,
+    }))?;
+    Ok(balance.balance)
+}
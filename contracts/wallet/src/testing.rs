@@ -0,0 +1,2030 @@
+use crate::action::{render_action, validate_template};
+use crate::contract::{
+    execute, instantiate, migrate, query, query_config, query_hot_wallets,
+    query_hot_wallets_by_action, query_hot_wallets_by_label, query_whitelisted_contracts,
+    CONTRACT_NAME, CONTRACT_VERSION, DEFAULT_UUSD_RESERVE,
+};
+use crate::error::ContractError;
+use crate::reserve::{assert_reserve, save_reserves};
+use crate::spending::{consume_spending_limits, remaining_allowances};
+use crate::state::{hot_wallet_configs, whitelisted_contracts, Config, CONFIG};
+use crate::swap::PairCw20HookMsg;
+use basset::airdrop::{MIRAirdropHandleMsg, PairHandleMsg};
+use basset::hub::{Cw20HookMsg as HubHookMsg, ExecuteMsg as HubExecuteMsg};
+use basset::mock_querier::{mock_dependencies as mock_terra_dependencies, MOCK_CONTRACT_ADDR};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    coins, from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, OwnedDeps,
+    Response, StdError, Storage, Uint128, WasmMsg,
+};
+use cw2::{get_contract_version, set_contract_version};
+use cw20::Cw20ExecuteMsg;
+use moneymarket::custody::{Cw20HookMsg as CustodyHookMsg, ExecuteMsg as CustodyExecuteMsg};
+use moneymarket::market::{
+    Cw20HookMsg as MarketHookMsg, EpochStateResponse, ExecuteMsg as MarketExecuteMsg,
+    StateResponse as MarketStateResponse,
+};
+use moneymarket::overseer::ExecuteMsg as OverseerExecuteMsg;
+use smartwallet::wallet::{
+    ActionFunds, ActionLimit, ActionParam, ActionParamSlot, ActionParamType, ActionTemplate,
+    Airdrop, AirdropsResponse, AssetInfo, ExecuteMsg, GasTank, GasTankStatus,
+    GasTankStatusResponse, HotWallet, InstantiateMsg, LoanConfig, MigrateMsg, QueryMsg,
+    ReservesResponse, SpendingLimit, SwapPair, UnbondRequestsResponse, WhitelistedContract,
+    WithdrawableUnbondedResponse,
+};
+
+// raw 0.2.0 config, written the way the deployed contract stored it
+const CONFIG_V0_2_JSON: &str = r#"{
+    "hot_wallets": [
+        {"address": "hot0", "label": "bot", "gas_cooldown": 3600, "gas_tank_max": "5000000", "whitelisted_messages": [0, 1]},
+        {"address": "hot1", "label": "keeper", "gas_cooldown": 60, "gas_tank_max": "1000000", "whitelisted_messages": [2]}
+    ],
+    "cw3_address": "multisig",
+    "whitelisted_contracts": [{"address": "market", "label": "anchor_market", "code_id": 1}]
+}"#;
+
+// raw 0.3.0 layout: config without hot wallets, hot wallets in a plain map
+const CONFIG_V0_3_JSON: &str = r#"{
+    "cw3_address": "multisig",
+    "whitelisted_contracts": [
+        {"address": "market", "label": "anchor_market", "code_id": 1},
+        {"address": "reward", "label": "bluna_reward", "code_id": 2}
+    ]
+}"#;
+
+const HOT_WALLET_V0_3_JSON: &str = r#"{"address": "hot0", "label": "bot", "gas_cooldown": 3600, "gas_tank_max": "5000000", "whitelisted_messages": [0, 1], "spending_limits": [], "action_limits": []}"#;
+
+fn store_v0_2_config(storage: &mut dyn Storage) {
+    storage.set(b"\x00\x06config", CONFIG_V0_2_JSON.as_bytes());
+}
+
+fn store_v0_3_state(storage: &mut dyn Storage) {
+    storage.set(b"\x00\x06config", CONFIG_V0_3_JSON.as_bytes());
+    storage.set(
+        b"\x00\x10hotwalletconfigshot0",
+        HOT_WALLET_V0_3_JSON.as_bytes(),
+    );
+    set_contract_version(storage, CONTRACT_NAME, "0.3.0").unwrap();
+}
+
+// 0.4.x: config in its current layout, hot wallets still with a single gas_tank_max
+fn store_v0_4_state(storage: &mut dyn Storage) {
+    storage.set(b"\x00\x06config", br#"{"cw3_address": "multisig"}"#);
+    storage.set(
+        b"\x00\x10hotwalletconfigshot0",
+        HOT_WALLET_V0_3_JSON.as_bytes(),
+    );
+    set_contract_version(storage, CONTRACT_NAME, "0.4.0").unwrap();
+}
+
+#[test]
+fn test_migrate_from_v0_2() {
+    let mut deps = mock_dependencies(&[]);
+    store_v0_2_config(&mut deps.storage);
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    let config = query_config(deps.as_ref()).unwrap();
+    assert_eq!(config.cw3_address, Addr::unchecked("multisig"));
+
+    let market = whitelisted_contracts()
+        .load(&deps.storage, "anchor_market")
+        .unwrap();
+    assert_eq!(market.address, "market");
+
+    let hot0 = hot_wallet_configs().load(&deps.storage, "hot0").unwrap();
+    assert_eq!(hot0.label, "bot");
+    assert_eq!(hot0.gas_tanks, vec![migrated_gas_tank(5000000u128)]);
+    assert_eq!(hot0.whitelisted_messages, vec![0, 1]);
+    assert!(hot0.spending_limits.is_empty());
+    assert!(hot0.action_limits.is_empty());
+
+    let hot1 = hot_wallet_configs().load(&deps.storage, "hot1").unwrap();
+    assert_eq!(hot1.whitelisted_messages, vec![2]);
+
+    let res = query_hot_wallets(deps.as_ref(), None, None).unwrap();
+    assert_eq!(res.hot_wallets.len(), 2);
+
+    // indexes are built for migrated entries
+    let res = query_hot_wallets_by_action(deps.as_ref(), 2, None, None).unwrap();
+    assert_eq!(res.hot_wallets, vec![hot1]);
+    let res = query_hot_wallets_by_label(deps.as_ref(), String::from("bot"), None, None).unwrap();
+    assert_eq!(res.hot_wallets, vec![hot0]);
+
+    let version = get_contract_version(&deps.storage).unwrap();
+    assert_eq!(version.contract, CONTRACT_NAME);
+    assert_eq!(version.version, CONTRACT_VERSION);
+}
+
+#[test]
+fn test_migrate_same_version() {
+    let mut deps = mock_dependencies(&[]);
+    store_v0_2_config(&mut deps.storage);
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    // state is already in the new layout, nothing to move
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    let res = query_hot_wallets(deps.as_ref(), None, None).unwrap();
+    assert_eq!(res.hot_wallets.len(), 2);
+}
+
+#[test]
+fn test_migrate_from_v0_3() {
+    let mut deps = mock_dependencies(&[]);
+    store_v0_3_state(&mut deps.storage);
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    let config = query_config(deps.as_ref()).unwrap();
+    assert_eq!(config.cw3_address, Addr::unchecked("multisig"));
+
+    let res = query_whitelisted_contracts(deps.as_ref(), None, None).unwrap();
+    let labels: Vec<String> = res
+        .whitelisted_contracts
+        .into_iter()
+        .map(|x| x.label)
+        .collect();
+    assert_eq!(labels, vec!["anchor_market", "bluna_reward"]);
+
+    // paginated
+    let res =
+        query_whitelisted_contracts(deps.as_ref(), Some(String::from("anchor_market")), Some(1))
+            .unwrap();
+    assert_eq!(res.whitelisted_contracts[0].label, "bluna_reward");
+
+    let res = query_hot_wallets_by_action(deps.as_ref(), 1, None, None).unwrap();
+    assert_eq!(res.hot_wallets[0].address, "hot0");
+    let res = query_hot_wallets_by_action(deps.as_ref(), 2, None, None).unwrap();
+    assert!(res.hot_wallets.is_empty());
+}
+
+#[test]
+fn test_migrate_from_v0_4() {
+    let mut deps = mock_dependencies(&[]);
+    store_v0_4_state(&mut deps.storage);
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    let hot0 = hot_wallet_configs().load(&deps.storage, "hot0").unwrap();
+    assert_eq!(hot0.gas_tanks, vec![migrated_gas_tank(5000000u128)]);
+
+    let res = query_hot_wallets_by_label(deps.as_ref(), String::from("bot"), None, None).unwrap();
+    assert_eq!(res.hot_wallets, vec![hot0]);
+
+    // the old fixed buffer carries over as the uusd reserve
+    let res: ReservesResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Reserves {}).unwrap()).unwrap();
+    assert_eq!(res.reserves, coins(DEFAULT_UUSD_RESERVE, "uusd"));
+}
+
+// 0.5.x kept airdrop contracts as raw addresses
+const AIRDROP_V0_5_JSON: &str = r#"{"label": "mir", "airdrop_contract": "mirairdrop", "token_contract": "mirtoken", "pair_contract": "mirpair", "max_spread": "0.01"}"#;
+
+fn store_v0_5_airdrop(storage: &mut dyn Storage, whitelisted: &[(&str, &str)]) {
+    store_v0_4_state(storage);
+    storage.set(b"\x00\x08airdropsmir", AIRDROP_V0_5_JSON.as_bytes());
+    for (label, address) in whitelisted.iter() {
+        whitelisted_contracts()
+            .save(
+                storage,
+                label,
+                &WhitelistedContract {
+                    address: address.to_string(),
+                    label: label.to_string(),
+                    code_id: 1,
+                },
+            )
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_migrate_airdrop_labels() {
+    // the pair isn't whitelisted
+    let mut deps = mock_dependencies(&[]);
+    store_v0_5_airdrop(
+        &mut deps.storage,
+        &[("mir_airdrop", "mirairdrop"), ("mir_token", "mirtoken")],
+    );
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "airdrop mir uses mirpair, which is not whitelisted"
+        ))
+    );
+
+    let mut deps = mock_dependencies(&[]);
+    store_v0_5_airdrop(
+        &mut deps.storage,
+        &[
+            ("mir_airdrop", "mirairdrop"),
+            ("mir_token", "mirtoken"),
+            ("mir_pair", "mirpair"),
+        ],
+    );
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    let res: AirdropsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Airdrops {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.airdrops,
+        vec![mir_airdrop(Some(String::from("mir_pair")))]
+    );
+}
+
+#[test]
+fn test_migrate_refuses_downgrade() {
+    let mut deps = mock_dependencies(&[]);
+    set_contract_version(&mut deps.storage, CONTRACT_NAME, "99.0.0").unwrap();
+
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MigrationDowngrade {
+            stored: String::from("99.0.0"),
+            current: String::from(CONTRACT_VERSION),
+        }
+    );
+}
+
+#[test]
+fn test_migrate_refuses_other_contract() {
+    let mut deps = mock_dependencies(&[]);
+    set_contract_version(&mut deps.storage, "crates.io:cw20-base", "0.8.1").unwrap();
+
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MigrationWrongContract {
+            stored: String::from("crates.io:cw20-base"),
+        }
+    );
+}
+
+#[test]
+fn test_migrate_refuses_unreadable_version() {
+    let mut deps = mock_dependencies(&[]);
+    deps.storage.set(b"contract_info", b"not json");
+
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert!(matches!(err, ContractError::Std(StdError::ParseErr { .. })));
+}
+
+fn uusd_gas_tank(max: u128) -> GasTank {
+    GasTank {
+        denom: String::from("uusd"),
+        max: Uint128::new(max),
+        buffer: Uint128::from(DEFAULT_UUSD_RESERVE),
+    }
+}
+
+// migrated tanks leave the old buffer to the uusd reserve
+fn migrated_gas_tank(max: u128) -> GasTank {
+    GasTank {
+        denom: String::from("uusd"),
+        max: Uint128::new(max),
+        buffer: Uint128::zero(),
+    }
+}
+
+fn store_hot_wallet(storage: &mut dyn Storage) {
+    CONFIG
+        .save(
+            storage,
+            &Config {
+                cw3_address: Addr::unchecked("multisig"),
+            },
+        )
+        .unwrap();
+    hot_wallet_configs()
+        .save(
+            storage,
+            "hot0",
+            &HotWallet {
+                address: String::from("hot0"),
+                label: String::from("bot"),
+                gas_cooldown: 3600,
+                gas_tanks: vec![uusd_gas_tank(5000000u128)],
+                whitelisted_messages: vec![3, 4, 5, 6, 7, 8, 9, 10, 12],
+                spending_limits: vec![],
+                action_limits: vec![],
+            },
+        )
+        .unwrap();
+}
+
+// every contract is deployed with the code id it is whitelisted under
+fn store_whitelisted_contracts(
+    deps: &mut OwnedDeps<MockStorage, MockApi, basset::mock_querier::WasmMockQuerier>,
+) {
+    let labels = [
+        "anchor_market",
+        "anchor_overseer",
+        "anchor_oracle",
+        "anchor_custody_bluna",
+        "bluna_token",
+        "anchor_aust",
+        "bluna_hub",
+        "bluna_reward",
+        "mir_airdrop",
+        "mir_token",
+        "mir_pair",
+    ];
+    for (code_id, label) in labels.iter().enumerate() {
+        deps.querier
+            .with_contract_info(&label.replace('_', ""), code_id as u64);
+        whitelisted_contracts()
+            .save(
+                &mut deps.storage,
+                label,
+                &WhitelistedContract {
+                    address: label.replace('_', ""),
+                    label: label.to_string(),
+                    code_id: code_id as u64,
+                },
+            )
+            .unwrap();
+    }
+}
+
+// 1000 ust worth of collateral: 100 bluna at 10 uusd
+fn mock_anchor_deps(
+    loan: u128,
+) -> OwnedDeps<MockStorage, MockApi, basset::mock_querier::WasmMockQuerier> {
+    let mut deps = mock_terra_dependencies(&[]);
+    deps.querier
+        .with_loan("anchormarket", MOCK_CONTRACT_ADDR, Uint256::from(loan));
+    deps.querier.with_collaterals(
+        "anchoroverseer",
+        MOCK_CONTRACT_ADDR,
+        vec![(String::from("blunatoken"), Uint256::from(100000000u128))],
+    );
+    deps.querier
+        .with_oracle_price("anchororacle", "blunatoken", Decimal256::percent(1000));
+    store_hot_wallet(&mut deps.storage);
+    store_whitelisted_contracts(&mut deps);
+    deps
+}
+
+fn set_loan_config(
+    deps: DepsMut,
+    target_ltv: Decimal256,
+    safe_ltv: Decimal256,
+) -> Result<Response, ContractError> {
+    execute(
+        deps,
+        mock_env(),
+        mock_info("multisig", &[]),
+        ExecuteMsg::UpdateLoanConfig {
+            loan_config: LoanConfig {
+                target_ltv,
+                safe_ltv,
+            },
+        },
+    )
+}
+
+#[test]
+fn test_fill_up_gas_deducts_tax() {
+    let mut deps = mock_terra_dependencies(&coins(200000000u128, "uusd"));
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&String::from("uusd"), &Uint128::new(1000000u128))],
+    );
+    deps.querier
+        .with_balances(&[(&String::from("hot0"), &coins(1000000u128, "uusd"))]);
+    store_hot_wallet(&mut deps.storage);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::FillUpGas {},
+    )
+    .unwrap();
+
+    // 4000000 needed, 1% tax comes out of the sent amount
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("hot0"),
+            amount: coins(3960396u128, "uusd"),
+        })
+    );
+}
+
+#[test]
+fn test_fill_up_gas_needs_smart_wallet_buffer() {
+    let mut deps = mock_terra_dependencies(&[]);
+    deps.querier.with_balances(&[
+        (
+            &String::from(MOCK_CONTRACT_ADDR),
+            &coins(4000000u128, "uusd"),
+        ),
+        (&String::from("hot0"), &coins(1000000u128, "uusd")),
+    ]);
+    store_hot_wallet(&mut deps.storage);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::FillUpGas {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SmartWalletGas {});
+}
+
+#[test]
+fn test_gas_tank_status() {
+    let mut deps = mock_terra_dependencies(&coins(200000000u128, "uusd"));
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&String::from("uusd"), &Uint128::new(1000000u128))],
+    );
+    deps.querier
+        .with_balances(&[(&String::from("hot0"), &coins(1000000u128, "uusd"))]);
+    store_hot_wallet(&mut deps.storage);
+
+    let status = |deps: &OwnedDeps<_, _, _>, env| -> GasTankStatusResponse {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::GasTankStatus {
+                    address: String::from("hot0"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    let expected = GasTankStatusResponse {
+        address: String::from("hot0"),
+        cooldown_left: 0,
+        tanks: vec![GasTankStatus {
+            denom: String::from("uusd"),
+            balance: Uint128::new(1000000u128),
+            max: Uint128::new(5000000u128),
+            deficit: Uint128::new(4000000u128),
+            tax: Uint128::new(39604u128),
+            refill_funded: true,
+        }],
+    };
+    assert_eq!(status(&deps, mock_env()), expected);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::FillUpGas {},
+    )
+    .unwrap();
+
+    // the mock bank doesn't move funds, so only the cooldown changes
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(600);
+    assert_eq!(
+        status(&deps, env),
+        GasTankStatusResponse {
+            cooldown_left: 3000,
+            ..expected
+        }
+    );
+
+    deps.querier.with_balances(&[
+        (
+            &String::from(MOCK_CONTRACT_ADDR),
+            &coins(100000000u128, "uusd"),
+        ),
+        (&String::from("hot0"), &coins(1000000u128, "uusd")),
+    ]);
+    assert!(!status(&deps, mock_env()).tanks[0].refill_funded);
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::GasTankStatus {
+            address: String::from("hot1"),
+        },
+    );
+    assert!(err.is_err());
+}
+
+#[test]
+fn test_borrow_stable_capped_at_target_ltv() {
+    let mut deps = mock_anchor_deps(400000000u128);
+    let borrow = ExecuteMsg::BorrowStable {
+        amount: Uint128::new(300000000u128),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        borrow.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::LoanConfigNotSet {});
+
+    let err =
+        set_loan_config(deps.as_mut(), Decimal256::one(), Decimal256::percent(40)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidLoanConfig {
+            reason: String::from("target_ltv must be between 0 and 1")
+        }
+    );
+    set_loan_config(
+        deps.as_mut(),
+        Decimal256::percent(50),
+        Decimal256::percent(40),
+    )
+    .unwrap();
+
+    // 1000 ust of collateral at 50% leaves 100 ust on top of the 400 ust loan
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        borrow.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("anchormarket"),
+            funds: vec![],
+            msg: to_binary(&MarketExecuteMsg::BorrowStable {
+                borrow_amount: Uint256::from(100000000u128),
+                to: None,
+            })
+            .unwrap(),
+        })
+    );
+
+    deps.querier.with_loan(
+        "anchormarket",
+        MOCK_CONTRACT_ADDR,
+        Uint256::from(500000000u128),
+    );
+    let err = execute(deps.as_mut(), mock_env(), mock_info("hot0", &[]), borrow).unwrap_err();
+    assert_eq!(err, ContractError::TargetLtvExceeded {});
+}
+
+#[test]
+fn test_deposit_collateral() {
+    let mut deps = mock_anchor_deps(0u128);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::DepositCollateral {
+            amount: Uint128::new(5000000u128),
+        },
+    )
+    .unwrap();
+
+    // custody first, then the overseer lock
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) => {
+            assert_eq!(contract_addr, "blunatoken");
+            assert_eq!(
+                from_binary::<Cw20ExecuteMsg>(msg).unwrap(),
+                Cw20ExecuteMsg::Send {
+                    contract: String::from("anchorcustodybluna"),
+                    amount: Uint128::new(5000000u128),
+                    msg: to_binary(&CustodyHookMsg::DepositCollateral {}).unwrap(),
+                }
+            );
+        }
+        msg => panic!("unexpected msg {:?}", msg),
+    }
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("anchoroverseer"),
+            funds: vec![],
+            msg: to_binary(&OverseerExecuteMsg::LockCollateral {
+                collaterals: vec![(String::from("blunatoken"), Uint256::from(5000000u128))],
+            })
+            .unwrap(),
+        })
+    );
+}
+
+#[test]
+fn test_deposit_collateral_refuses_migrated_custody() {
+    let mut deps = mock_anchor_deps(0u128);
+    deps.querier.with_contract_info("anchorcustodybluna", 99u64);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::DepositCollateral {
+            amount: Uint128::new(5000000u128),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CodeIdMismatch {
+            address: String::from("anchorcustodybluna"),
+            expected: 3u64,
+            actual: 99u64,
+        }
+    );
+}
+
+#[test]
+fn test_withdraw_collateral_keeps_target_ltv() {
+    let mut deps = mock_anchor_deps(400000000u128);
+    set_loan_config(
+        deps.as_mut(),
+        Decimal256::percent(50),
+        Decimal256::percent(40),
+    )
+    .unwrap();
+
+    // 700 ust of collateral left can't carry a 400 ust loan at 50%
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::WithdrawCollateral {
+            amount: Uint128::new(30000000u128),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::TargetLtvExceeded {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::WithdrawCollateral {
+            amount: Uint128::new(10000000u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("anchoroverseer"),
+            funds: vec![],
+            msg: to_binary(&OverseerExecuteMsg::UnlockCollateral {
+                collaterals: vec![(String::from("blunatoken"), Uint256::from(10000000u128))],
+            })
+            .unwrap(),
+        })
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("anchorcustodybluna"),
+            funds: vec![],
+            msg: to_binary(&CustodyExecuteMsg::WithdrawCollateral {
+                amount: Some(Uint256::from(10000000u128)),
+            })
+            .unwrap(),
+        })
+    );
+}
+
+// 450 ust borrowed against 1000 ust of collateral, 50 ust above the 40% safe ltv
+fn mock_rebalance_deps(
+    uusd_balance: u128,
+    aust_balance: u128,
+) -> OwnedDeps<MockStorage, MockApi, basset::mock_querier::WasmMockQuerier> {
+    let mut deps = mock_anchor_deps(450000000u128);
+    deps.querier.with_balances(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &coins(uusd_balance, "uusd"),
+    )]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&String::from("uusd"), &Uint128::new(1000000u128))],
+    );
+    deps.querier.with_market_state(
+        "anchormarket",
+        MarketStateResponse {
+            total_liabilities: Decimal256::zero(),
+            total_reserves: Decimal256::zero(),
+            last_interest_updated: 0u64,
+            last_reward_updated: 0u64,
+            global_interest_index: Decimal256::one(),
+            global_reward_index: Decimal256::zero(),
+            anc_emission_rate: Decimal256::zero(),
+            prev_aterra_supply: Uint256::zero(),
+            prev_exchange_rate: Decimal256::one(),
+        },
+        EpochStateResponse {
+            exchange_rate: Decimal256::percent(120),
+            aterra_supply: Uint256::zero(),
+        },
+    );
+    deps.querier.with_token_balances(
+        "anchoraust",
+        &[(
+            &String::from(MOCK_CONTRACT_ADDR),
+            &Uint128::new(aust_balance),
+        )],
+    );
+    set_loan_config(
+        deps.as_mut(),
+        Decimal256::percent(50),
+        Decimal256::percent(40),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("multisig", &[]),
+        ExecuteMsg::UpdateReserves {
+            reserves: coins(DEFAULT_UUSD_RESERVE, "uusd"),
+        },
+    )
+    .unwrap();
+    deps
+}
+
+fn repay_msg(amount: u128) -> CosmosMsg {
+    CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: String::from("anchormarket"),
+        funds: coins(amount, "uusd"),
+        msg: to_binary(&MarketExecuteMsg::RepayStable {}).unwrap(),
+    })
+}
+
+#[test]
+fn test_rebalance_loan_from_wallet_balance() {
+    let mut deps = mock_rebalance_deps(200000000u128, 0u128);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::RebalanceLoan {},
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].msg, repay_msg(50000000u128));
+
+    // back at 400 ust there is nothing left to repay
+    deps.querier.with_loan(
+        "anchormarket",
+        MOCK_CONTRACT_ADDR,
+        Uint256::from(400000000u128),
+    );
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::RebalanceLoan {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::LoanWithinSafeLtv {});
+}
+
+#[test]
+fn test_rebalance_loan_redeems_aust_shortfall() {
+    // 20 ust above the uusd reserve, the other 30 ust comes from aust
+    let mut deps = mock_rebalance_deps(120000000u128, 100000000u128);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::RebalanceLoan {},
+    )
+    .unwrap();
+
+    // 30.5 ust, the repay's 1% tax included, plus the market's 1% tax at 1.2 uusd per aust
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("anchoraust"),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: String::from("anchormarket"),
+                amount: Uint128::new(25670834u128),
+                msg: to_binary(&MarketHookMsg::RedeemStable {}).unwrap(),
+            })
+            .unwrap(),
+        })
+    );
+    assert_eq!(res.messages[1].msg, repay_msg(50000000u128));
+
+    // exactly the repay above the reserve still leaves its tax to cover
+    let mut deps = mock_rebalance_deps(150000000u128, 100000000u128);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::RebalanceLoan {},
+    )
+    .unwrap();
+    assert_eq!(res.messages[0].msg, redeem_msg(420834u128));
+    assert_eq!(res.messages[1].msg, repay_msg(50000000u128));
+
+    let mut deps = mock_rebalance_deps(120000000u128, 10000000u128);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::RebalanceLoan {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientRepayFunds {
+            amount: String::from("50000000")
+        }
+    );
+}
+
+#[test]
+fn test_safe_ltv_within_target_ltv() {
+    let mut deps = mock_anchor_deps(0u128);
+
+    let err = set_loan_config(
+        deps.as_mut(),
+        Decimal256::percent(50),
+        Decimal256::percent(60),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidLoanConfig {
+            reason: String::from("safe_ltv must be between 0 and target_ltv")
+        }
+    );
+}
+
+fn redeem_msg(amount: u128) -> CosmosMsg {
+    CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: String::from("anchoraust"),
+        funds: vec![],
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: String::from("anchormarket"),
+            amount: Uint128::new(amount),
+            msg: to_binary(&MarketHookMsg::RedeemStable {}).unwrap(),
+        })
+        .unwrap(),
+    })
+}
+
+#[test]
+fn test_anchor_earn_redeem_capped() {
+    let mut deps = mock_terra_dependencies(&[]);
+    store_hot_wallet(&mut deps.storage);
+    store_whitelisted_contracts(&mut deps);
+    deps.querier.with_token_balances(
+        "anchoraust",
+        &[(
+            &String::from(MOCK_CONTRACT_ADDR),
+            &Uint128::new(30000000u128),
+        )],
+    );
+    hot_wallet_configs()
+        .update(&mut deps.storage, "hot0", |x| -> Result<_, ContractError> {
+            let mut hot_wallet = x.unwrap();
+            hot_wallet.spending_limits = vec![SpendingLimit {
+                denom: String::from("anchoraust"),
+                max_amount: Uint128::new(40000000u128),
+                window: 86400,
+            }];
+            Ok(hot_wallet)
+        })
+        .unwrap();
+
+    // no more than the wallet holds
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::AnchorEarnRedeem {
+            amount: Uint128::new(50000000u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages[0].msg, redeem_msg(30000000u128));
+
+    // 30 of the 40 aust allowance is used up
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::AnchorEarnRedeem {
+            amount: Uint128::new(20000000u128),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SpendingLimitExceeded {
+            denom: String::from("anchoraust")
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::AnchorEarnRedeem {
+            amount: Uint128::new(10000000u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages[0].msg, redeem_msg(10000000u128));
+}
+
+const VALIDATOR: &str = "terravaloper1krj7amhhagjnyg2tkkuh6l0550y733jnjnnlzy";
+
+#[test]
+fn test_bluna_bond_needs_approved_validator() {
+    let mut deps = mock_terra_dependencies(&coins(5000000u128, "uluna"));
+    store_hot_wallet(&mut deps.storage);
+    store_whitelisted_contracts(&mut deps);
+
+    let bond = ExecuteMsg::BlunaBond {
+        amount: Uint128::new(10000000u128),
+        validator: String::from(VALIDATOR),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        bond.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ValidatorNotApproved {});
+
+    let update_validators = ExecuteMsg::UpdateBondValidators {
+        validators: vec![String::from(VALIDATOR)],
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        update_validators.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let invalid = vec![
+        (vec![], "no validators given"),
+        (
+            vec![String::from("terra1krj7amhhagjnyg2tkkuh6l0550y733jnjnnlzy")],
+            "terra1krj7amhhagjnyg2tkkuh6l0550y733jnjnnlzy is not a validator address",
+        ),
+        (
+            vec![String::from("terravaloper1bad")],
+            "terravaloper1bad is not a validator address",
+        ),
+        (
+            vec![String::from(VALIDATOR), String::from(VALIDATOR)],
+            "duplicate validator terravaloper1krj7amhhagjnyg2tkkuh6l0550y733jnjnnlzy",
+        ),
+    ];
+    for (validators, reason) in invalid.into_iter() {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("multisig", &[]),
+            ExecuteMsg::UpdateBondValidators { validators },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidBondValidators {
+                reason: String::from(reason)
+            }
+        );
+    }
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("multisig", &[]),
+        update_validators,
+    )
+    .unwrap();
+
+    // capped at the 5 luna the wallet holds
+    let res = execute(deps.as_mut(), mock_env(), mock_info("hot0", &[]), bond).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("blunahub"),
+            funds: coins(5000000u128, "uluna"),
+            msg: to_binary(&HubExecuteMsg::Bond {
+                validator: String::from(VALIDATOR),
+            })
+            .unwrap(),
+        })
+    );
+}
+
+#[test]
+fn test_bluna_unbond_and_withdraw() {
+    let mut deps = mock_terra_dependencies(&[]);
+    store_hot_wallet(&mut deps.storage);
+    store_whitelisted_contracts(&mut deps);
+    deps.querier.with_token_balances(
+        "blunatoken",
+        &[(
+            &String::from(MOCK_CONTRACT_ADDR),
+            &Uint128::new(3000000u128),
+        )],
+    );
+    deps.querier.with_unbonds(
+        "blunahub",
+        MOCK_CONTRACT_ADDR,
+        Uint128::new(2000000u128),
+        vec![(7u64, Uint128::new(1000000u128))],
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::BlunaUnbond {
+            amount: Uint128::new(5000000u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("blunatoken"),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: String::from("blunahub"),
+                amount: Uint128::new(3000000u128),
+                msg: to_binary(&HubHookMsg::Unbond {}).unwrap(),
+            })
+            .unwrap(),
+        })
+    );
+
+    let res: UnbondRequestsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::UnbondRequests {}).unwrap())
+            .unwrap();
+    assert_eq!(res.requests, vec![(7u64, Uint128::new(1000000u128))]);
+
+    let res: WithdrawableUnbondedResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::WithdrawableUnbonded {}).unwrap())
+            .unwrap();
+    assert_eq!(res.withdrawable, Uint128::new(2000000u128));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::BlunaWithdrawUnbonded {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("blunahub"),
+            funds: vec![],
+            msg: to_binary(&HubExecuteMsg::WithdrawUnbonded {}).unwrap(),
+        })
+    );
+}
+
+fn mir_airdrop(pair_label: Option<String>) -> Airdrop {
+    Airdrop {
+        label: String::from("mir"),
+        airdrop_label: String::from("mir_airdrop"),
+        token_label: String::from("mir_token"),
+        pair_label,
+        max_spread: Decimal::percent(1),
+    }
+}
+
+fn claim_airdrop(swap: bool, belief_price: Option<Decimal>) -> ExecuteMsg {
+    ExecuteMsg::ClaimAirdrop {
+        label: String::from("mir"),
+        stage: 2u8,
+        amount: Uint128::new(1000000u128),
+        proof: vec![String::from("abcd")],
+        swap,
+        belief_price,
+    }
+}
+
+#[test]
+fn test_claim_airdrop() {
+    let mut deps = mock_terra_dependencies(&[]);
+    store_hot_wallet(&mut deps.storage);
+    store_whitelisted_contracts(&mut deps);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        claim_airdrop(false, None),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AirdropNotFound {});
+
+    let mut airdrop = mir_airdrop(None);
+    airdrop.max_spread = Decimal::one();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("multisig", &[]),
+        ExecuteMsg::UpsertAirdrop { airdrop },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAirdrop {
+            reason: String::from("max_spread must be below 1")
+        }
+    );
+
+    // contracts are resolved through the whitelist when claiming
+    let mut airdrop = mir_airdrop(None);
+    airdrop.airdrop_label = String::from("ust_airdrop");
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("multisig", &[]),
+        ExecuteMsg::UpsertAirdrop { airdrop },
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        claim_airdrop(false, None),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ContractNotWhitelisted {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("multisig", &[]),
+        ExecuteMsg::UpsertAirdrop {
+            airdrop: mir_airdrop(None),
+        },
+    )
+    .unwrap();
+
+    let claim_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: String::from("mirairdrop"),
+        funds: vec![],
+        msg: to_binary(&MIRAirdropHandleMsg::Claim {
+            stage: 2u8,
+            amount: Uint128::new(1000000u128),
+            proof: vec![String::from("abcd")],
+        })
+        .unwrap(),
+    });
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        claim_airdrop(false, None),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].msg, claim_msg);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        claim_airdrop(true, Some(Decimal::percent(50))),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AirdropSwapNotConfigured {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("multisig", &[]),
+        ExecuteMsg::UpsertAirdrop {
+            airdrop: mir_airdrop(Some(String::from("mir_pair"))),
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        claim_airdrop(true, None),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidSwap {
+            reason: String::from("belief_price must be non-zero")
+        }
+    );
+
+    // the claimed tokens are sold right after, at the configured max spread
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        claim_airdrop(true, Some(Decimal::percent(50))),
+    )
+    .unwrap();
+    assert_eq!(res.messages[0].msg, claim_msg);
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("mirtoken"),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: String::from("mirpair"),
+                amount: Uint128::new(1000000u128),
+                msg: to_binary(&PairHandleMsg::Swap {
+                    belief_price: Some(Decimal::percent(50)),
+                    max_spread: Some(Decimal::percent(1)),
+                    to: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+        })
+    );
+
+    let res: AirdropsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Airdrops {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.airdrops,
+        vec![mir_airdrop(Some(String::from("mir_pair")))]
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("multisig", &[]),
+        ExecuteMsg::RemoveAirdrop {
+            label: String::from("mir"),
+        },
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        claim_airdrop(false, None),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AirdropNotFound {});
+}
+
+fn limited_hot_wallet(spending_limits: Vec<SpendingLimit>) -> HotWallet {
+    HotWallet {
+        address: String::from("hot0"),
+        label: String::from("bot"),
+        gas_cooldown: 3600,
+        gas_tanks: vec![],
+        whitelisted_messages: vec![],
+        spending_limits,
+        action_limits: vec![],
+    }
+}
+
+#[test]
+fn test_spending_limits() {
+    let mut storage = MockStorage::new();
+    let hot_wallet = limited_hot_wallet(vec![
+        SpendingLimit {
+            denom: String::from("uusd"),
+            max_amount: Uint128::new(100u128),
+            window: 3600,
+        },
+        SpendingLimit {
+            denom: String::from("uusd"),
+            max_amount: Uint128::new(150u128),
+            window: 86400,
+        },
+    ]);
+    let exceeded = || ContractError::SpendingLimitExceeded {
+        denom: String::from("uusd"),
+    };
+
+    consume_spending_limits(&mut storage, &hot_wallet, &coins(60u128, "uusd"), 0).unwrap();
+    let err =
+        consume_spending_limits(&mut storage, &hot_wallet, &coins(50u128, "uusd"), 10).unwrap_err();
+    assert_eq!(err, exceeded());
+
+    // denoms without a limit go through and aren't recorded
+    consume_spending_limits(&mut storage, &hot_wallet, &coins(1000u128, "uluna"), 10).unwrap();
+
+    // the hourly window has rolled over, the daily one hasn't
+    consume_spending_limits(&mut storage, &hot_wallet, &coins(80u128, "uusd"), 3600).unwrap();
+    let err = consume_spending_limits(&mut storage, &hot_wallet, &coins(20u128, "uusd"), 7200)
+        .unwrap_err();
+    assert_eq!(err, exceeded());
+    consume_spending_limits(&mut storage, &hot_wallet, &coins(70u128, "uusd"), 86400).unwrap();
+
+    let allowances = remaining_allowances(&storage, &hot_wallet, 86400).unwrap();
+    assert_eq!(allowances[0].remaining, Uint128::new(30u128));
+    assert_eq!(allowances[1].remaining, Uint128::zero());
+}
+
+#[test]
+fn test_spending_limit_overflow() {
+    let mut storage = MockStorage::new();
+    let hot_wallet = limited_hot_wallet(vec![SpendingLimit {
+        denom: String::from("uusd"),
+        max_amount: Uint128::MAX,
+        window: u64::MAX,
+    }]);
+
+    consume_spending_limits(&mut storage, &hot_wallet, &coins(u128::MAX, "uusd"), 0).unwrap();
+    let err =
+        consume_spending_limits(&mut storage, &hot_wallet, &coins(1u128, "uusd"), 1).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SpendingLimitExceeded {
+            denom: String::from("uusd")
+        }
+    );
+}
+
+fn transfer_template() -> ActionTemplate {
+    ActionTemplate {
+        label: String::from("transfer"),
+        contract_label: String::from("bluna_token"),
+        msg: String::from(
+            r#"{"transfer":{"recipient":"$recipient","amount":"$amount","memo":"$memo"}}"#,
+        ),
+        params: vec![
+            ActionParamSlot {
+                name: String::from("recipient"),
+                param_type: ActionParamType::Address,
+            },
+            ActionParamSlot {
+                name: String::from("amount"),
+                param_type: ActionParamType::Uint128,
+            },
+            ActionParamSlot {
+                name: String::from("memo"),
+                param_type: ActionParamType::String,
+            },
+        ],
+        funds: Some(ActionFunds {
+            denom: String::from("uusd"),
+            max_amount: Uint128::new(1000u128),
+            amount_param: String::from("amount"),
+        }),
+    }
+}
+
+fn action_params(amount: &str, memo: &str) -> Vec<ActionParam> {
+    vec![
+        ActionParam {
+            name: String::from("recipient"),
+            value: String::from("owner"),
+        },
+        ActionParam {
+            name: String::from("amount"),
+            value: String::from(amount),
+        },
+        ActionParam {
+            name: String::from("memo"),
+            value: String::from(memo),
+        },
+    ]
+}
+
+#[test]
+fn test_validate_action_template() {
+    validate_template(&transfer_template()).unwrap();
+
+    let invalid = |reason: &str| ContractError::InvalidActionTemplate {
+        reason: String::from(reason),
+    };
+
+    let mut template = transfer_template();
+    template.msg = String::from(r#"{"transfer":"#);
+    assert_eq!(
+        validate_template(&template).unwrap_err(),
+        invalid("msg is not valid json")
+    );
+
+    let mut template = transfer_template();
+    template.params[2].name = String::from("amount");
+    assert_eq!(
+        validate_template(&template).unwrap_err(),
+        invalid("bad param name amount")
+    );
+
+    let mut template = transfer_template();
+    template.funds.as_mut().unwrap().amount_param = String::from("memo");
+    assert_eq!(
+        validate_template(&template).unwrap_err(),
+        invalid("funds amount_param must be a uint128 param")
+    );
+}
+
+#[test]
+fn test_render_action() {
+    let deps = mock_dependencies(&[]);
+    let template = transfer_template();
+
+    // string params are json escaped, so they can't break out of their slot
+    let (msg, funds) = render_action(
+        deps.as_ref(),
+        &template,
+        &action_params("500", r#"x","amount":"999"#),
+    )
+    .unwrap();
+    assert_eq!(
+        msg,
+        r#"{"transfer":{"recipient":"owner","amount":"500","memo":"x\",\"amount\":\"999"}}"#
+    );
+    assert_eq!(funds, coins(500u128, "uusd"));
+
+    let (_, funds) = render_action(deps.as_ref(), &template, &action_params("0", "")).unwrap();
+    assert!(funds.is_empty());
+
+    // a value that looks like a placeholder isn't filled in again, whatever the slot order
+    let mut reordered = transfer_template();
+    reordered.params.rotate_right(1);
+    let (msg, _) =
+        render_action(deps.as_ref(), &reordered, &action_params("500", "$amount")).unwrap();
+    assert_eq!(
+        msg,
+        r#"{"transfer":{"recipient":"owner","amount":"500","memo":"$amount"}}"#
+    );
+
+    let invalid = |name: &str| ContractError::InvalidActionParam {
+        name: String::from(name),
+    };
+
+    let err = render_action(deps.as_ref(), &template, &action_params("5.5", "")).unwrap_err();
+    assert_eq!(err, invalid("amount"));
+
+    let err = render_action(deps.as_ref(), &template, &action_params("500", "")[..2]).unwrap_err();
+    assert_eq!(err, invalid("memo"));
+
+    let mut params = action_params("500", "");
+    params.push(ActionParam {
+        name: String::from("to"),
+        value: String::from("owner"),
+    });
+    let err = render_action(deps.as_ref(), &template, &params).unwrap_err();
+    assert_eq!(err, invalid("to"));
+
+    let err = render_action(deps.as_ref(), &template, &action_params("1001", "")).unwrap_err();
+    assert_eq!(err, ContractError::ActionFundsExceeded {});
+}
+
+// transfer_template without coins attached, registered as id 1000 and enabled for hot0
+fn mock_cw20_action_deps(
+    spending_limits: Vec<SpendingLimit>,
+) -> OwnedDeps<MockStorage, MockApi, basset::mock_querier::WasmMockQuerier> {
+    let mut deps = mock_anchor_deps(0u128);
+    let mut template = transfer_template();
+    template.funds = None;
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("multisig", &[]),
+        ExecuteMsg::AddActionTemplate { template },
+    )
+    .unwrap();
+    hot_wallet_configs()
+        .update(&mut deps.storage, "hot0", |x| -> Result<_, ContractError> {
+            let mut hot_wallet = x.unwrap();
+            hot_wallet.whitelisted_messages.push(1000);
+            hot_wallet.spending_limits = spending_limits;
+            Ok(hot_wallet)
+        })
+        .unwrap();
+    deps
+}
+
+fn run_cw20_action(deps: DepsMut, amount: &str) -> Result<Response, ContractError> {
+    execute(
+        deps,
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::RunAction {
+            id: 1000,
+            params: action_params(amount, ""),
+        },
+    )
+}
+
+#[test]
+fn test_run_action_counts_cw20_against_spending_limits() {
+    let mut deps = mock_cw20_action_deps(vec![SpendingLimit {
+        denom: String::from("blunatoken"),
+        max_amount: Uint128::new(1000u128),
+        window: 86400,
+    }]);
+
+    run_cw20_action(deps.as_mut(), "600").unwrap();
+    let err = run_cw20_action(deps.as_mut(), "600").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SpendingLimitExceeded {
+            denom: String::from("blunatoken"),
+        }
+    );
+    run_cw20_action(deps.as_mut(), "400").unwrap();
+}
+
+#[test]
+fn test_run_action_keeps_cw20_reserve() {
+    let mut deps = mock_cw20_action_deps(vec![]);
+    deps.querier.with_token_balances(
+        "blunatoken",
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1000u128))],
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("multisig", &[]),
+        ExecuteMsg::UpdateReserves {
+            reserves: vec![Coin {
+                denom: String::from("blunatoken"),
+                amount: Uint128::new(500u128),
+            }],
+        },
+    )
+    .unwrap();
+
+    let err = run_cw20_action(deps.as_mut(), "600").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ReserveBreached {
+            denom: String::from("blunatoken"),
+            reserve: Uint128::new(500u128),
+        }
+    );
+    run_cw20_action(deps.as_mut(), "500").unwrap();
+}
+
+#[test]
+fn test_assert_reserve() {
+    let mut storage = MockStorage::new();
+    save_reserves(&mut storage, &coins(100u128, "uusd")).unwrap();
+    let breached = ContractError::ReserveBreached {
+        denom: String::from("uusd"),
+        reserve: Uint128::new(100u128),
+    };
+
+    assert_reserve(
+        &storage,
+        "uusd",
+        Uint128::new(1000u128),
+        Uint128::new(900u128),
+    )
+    .unwrap();
+    let err = assert_reserve(
+        &storage,
+        "uusd",
+        Uint128::new(1000u128),
+        Uint128::new(901u128),
+    )
+    .unwrap_err();
+    assert_eq!(err, breached);
+
+    // an amount the reserve would overflow errors instead of panicking
+    let err = assert_reserve(&storage, "uusd", Uint128::new(1000u128), Uint128::MAX).unwrap_err();
+    assert_eq!(err, breached);
+}
+
+#[test]
+fn test_hot_wallet_limits_validation() {
+    let mut deps = mock_dependencies(&[]);
+    store_hot_wallet(&mut deps.storage);
+
+    let mut uncounted = limited_hot_wallet(vec![]);
+    uncounted.action_limits = vec![ActionLimit {
+        action_id: 3,
+        cooldown: 0,
+        max_calls: Some(5),
+        period: 0,
+    }];
+    let mut duplicate_action = limited_hot_wallet(vec![]);
+    duplicate_action.action_limits = vec![
+        ActionLimit {
+            action_id: 3,
+            cooldown: 60,
+            max_calls: None,
+            period: 0,
+        },
+        ActionLimit {
+            action_id: 3,
+            cooldown: 0,
+            max_calls: Some(5),
+            period: 3600,
+        },
+    ];
+    let zero_window = limited_hot_wallet(vec![SpendingLimit {
+        denom: String::from("uusd"),
+        max_amount: Uint128::new(100u128),
+        window: 0,
+    }]);
+    let duplicate_window = limited_hot_wallet(vec![
+        SpendingLimit {
+            denom: String::from("uusd"),
+            max_amount: Uint128::new(100u128),
+            window: 3600,
+        },
+        SpendingLimit {
+            denom: String::from("uusd"),
+            max_amount: Uint128::new(200u128),
+            window: 3600,
+        },
+    ]);
+
+    let cases = vec![
+        (
+            uncounted,
+            ContractError::InvalidActionLimits {
+                reason: String::from("max_calls for action 3 needs a non-zero period"),
+            },
+        ),
+        (
+            duplicate_action,
+            ContractError::InvalidActionLimits {
+                reason: String::from("duplicate limit for action 3"),
+            },
+        ),
+        (
+            zero_window,
+            ContractError::InvalidSpendingLimits {
+                reason: String::from("window for uusd must be non-zero"),
+            },
+        ),
+        (
+            duplicate_window,
+            ContractError::InvalidSpendingLimits {
+                reason: String::from("duplicate 3600s limit for uusd"),
+            },
+        ),
+    ];
+
+    for (hot_wallet, expected) in cases.into_iter() {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("multisig", &[]),
+            ExecuteMsg::UpsertHot {
+                hot_wallet: hot_wallet.clone(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, expected);
+
+        let err = instantiate(
+            mock_dependencies(&[]).as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            InstantiateMsg::ExistingMultiSig {
+                hot_wallets: vec![hot_wallet],
+                cw3_address: String::from("multisig"),
+                whitelisted_contracts: vec![],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, expected);
+    }
+
+    // a cooldown alone needs no period
+    let mut cooldown_only = limited_hot_wallet(vec![]);
+    cooldown_only.action_limits = vec![ActionLimit {
+        action_id: 3,
+        cooldown: 60,
+        max_calls: None,
+        period: 0,
+    }];
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("multisig", &[]),
+        ExecuteMsg::UpsertHot {
+            hot_wallet: cooldown_only,
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_bluna_compound_without_rewards() {
+    let mut deps = mock_terra_dependencies(&[]);
+    store_hot_wallet(&mut deps.storage);
+    store_whitelisted_contracts(&mut deps);
+    hot_wallet_configs()
+        .update(&mut deps.storage, "hot0", |x| -> Result<_, ContractError> {
+            let mut hot_wallet = x.unwrap();
+            hot_wallet.whitelisted_messages.push(11);
+            Ok(hot_wallet)
+        })
+        .unwrap();
+    deps.querier.with_accrued_rewards(
+        "blunareward",
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        ExecuteMsg::BlunaCompound {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NothingToCompound {});
+}
+
+fn mir_swap_pair() -> SwapPair {
+    SwapPair {
+        label: String::from("mir_ust"),
+        contract_label: String::from("mir_pair"),
+        offer_asset: AssetInfo::Token {
+            contract_addr: String::from("mirtoken"),
+        },
+        ask_asset: AssetInfo::NativeToken {
+            denom: String::from("uusd"),
+        },
+        max_spread: Decimal::percent(1),
+        min_belief_price: Decimal::percent(200),
+        max_belief_price: Decimal::percent(400),
+    }
+}
+
+fn swap_mir(amount: u128) -> ExecuteMsg {
+    swap_mir_at(amount, Decimal::percent(300))
+}
+
+fn swap_mir_at(amount: u128, belief_price: Decimal) -> ExecuteMsg {
+    ExecuteMsg::Swap {
+        pair: String::from("mir_ust"),
+        offer_amount: Uint128::new(amount),
+        belief_price,
+        max_spread: Decimal::percent(1),
+        min_receive: Uint128::zero(),
+    }
+}
+
+#[test]
+fn test_swap_pairs() {
+    let mut deps = mock_terra_dependencies(&[]);
+    store_hot_wallet(&mut deps.storage);
+    store_whitelisted_contracts(&mut deps);
+    hot_wallet_configs()
+        .update(&mut deps.storage, "hot0", |x| -> Result<_, ContractError> {
+            let mut hot_wallet = x.unwrap();
+            hot_wallet.whitelisted_messages.push(13);
+            Ok(hot_wallet)
+        })
+        .unwrap();
+    deps.querier.with_token_balances(
+        "mirtoken",
+        &[(
+            &String::from(MOCK_CONTRACT_ADDR),
+            &Uint128::new(1000000u128),
+        )],
+    );
+
+    let upsert = |deps: DepsMut, swap_pair: SwapPair| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("multisig", &[]),
+            ExecuteMsg::UpsertSwapPair { swap_pair },
+        )
+    };
+
+    let mut swap_pair = mir_swap_pair();
+    swap_pair.ask_asset = swap_pair.offer_asset.clone();
+    let err = upsert(deps.as_mut(), swap_pair).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidSwapPair {
+            reason: String::from("offer and ask asset are the same")
+        }
+    );
+
+    let mut swap_pair = mir_swap_pair();
+    swap_pair.max_spread = Decimal::one();
+    let err = upsert(deps.as_mut(), swap_pair).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidSwapPair {
+            reason: String::from("max_spread must be below 1")
+        }
+    );
+
+    let mut swap_pair = mir_swap_pair();
+    swap_pair.min_belief_price = Decimal::zero();
+    let err = upsert(deps.as_mut(), swap_pair).unwrap_err();
+    let bad_range = ContractError::InvalidSwapPair {
+        reason: String::from("belief price range must be non-zero and ordered"),
+    };
+    assert_eq!(err, bad_range);
+    let mut swap_pair = mir_swap_pair();
+    swap_pair.min_belief_price = Decimal::percent(500);
+    let err = upsert(deps.as_mut(), swap_pair).unwrap_err();
+    assert_eq!(err, bad_range);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        swap_mir(1000u128),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SwapPairNotFound {});
+
+    // the cw20 offered has to be a whitelisted contract
+    let mut swap_pair = mir_swap_pair();
+    swap_pair.offer_asset = AssetInfo::Token {
+        contract_addr: String::from("fakemir"),
+    };
+    upsert(deps.as_mut(), swap_pair).unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        swap_mir(1000u128),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ContractNotWhitelisted {});
+
+    upsert(deps.as_mut(), mir_swap_pair()).unwrap();
+
+    // belief_price has to be within the multisig's range
+    for belief_price in [Decimal::percent(199), Decimal::percent(401)].iter() {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hot0", &[]),
+            swap_mir_at(1000u128, *belief_price),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidSwap {
+                reason: String::from("belief_price outside the pair's 2-4"),
+            }
+        );
+    }
+
+    // nothing to receive can't be guarded
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        swap_mir(2u128),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidSwap {
+            reason: String::from("offer_amount too small to receive anything"),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hot0", &[]),
+        swap_mir(1000u128),
+    )
+    .unwrap();
+    // 1000 / 3 less the 1% spread, though the caller asked for nothing
+    assert!(res
+        .attributes
+        .iter()
+        .any(|x| x.key == "min_receive" && x.value == "330"));
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("mirtoken"),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: String::from("mirpair"),
+                amount: Uint128::new(1000u128),
+                msg: to_binary(&PairCw20HookMsg::Swap {
+                    belief_price: Some(Decimal::percent(300)),
+                    max_spread: Some(Decimal::percent(1)),
+                    to: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+        })
+    );
+}
+
+#[test]
+fn test_loan_actions_count_against_spending_limits() {
+    let mut deps = mock_anchor_deps(0u128);
+    set_loan_config(
+        deps.as_mut(),
+        Decimal256::percent(50),
+        Decimal256::percent(40),
+    )
+    .unwrap();
+    hot_wallet_configs()
+        .update(&mut deps.storage, "hot0", |x| -> Result<_, ContractError> {
+            let mut hot_wallet = x.unwrap();
+            hot_wallet.spending_limits = vec![
+                SpendingLimit {
+                    denom: String::from("uusd"),
+                    max_amount: Uint128::new(100000000u128),
+                    window: 86400,
+                },
+                SpendingLimit {
+                    denom: String::from("blunatoken"),
+                    max_amount: Uint128::new(10000000u128),
+                    window: 86400,
+                },
+            ];
+            Ok(hot_wallet)
+        })
+        .unwrap();
+
+    let run =
+        |deps: DepsMut, msg: ExecuteMsg| execute(deps, mock_env(), mock_info("hot0", &[]), msg);
+    let exceeded = |denom: &str| ContractError::SpendingLimitExceeded {
+        denom: String::from(denom),
+    };
+
+    let err = run(
+        deps.as_mut(),
+        ExecuteMsg::BorrowStable {
+            amount: Uint128::new(150000000u128),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, exceeded("uusd"));
+    run(
+        deps.as_mut(),
+        ExecuteMsg::BorrowStable {
+            amount: Uint128::new(100000000u128),
+        },
+    )
+    .unwrap();
+
+    // deposits and withdrawals share the bluna allowance
+    run(
+        deps.as_mut(),
+        ExecuteMsg::DepositCollateral {
+            amount: Uint128::new(6000000u128),
+        },
+    )
+    .unwrap();
+    let err = run(
+        deps.as_mut(),
+        ExecuteMsg::WithdrawCollateral {
+            amount: Uint128::new(5000000u128),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, exceeded("blunatoken"));
+    run(
+        deps.as_mut(),
+        ExecuteMsg::WithdrawCollateral {
+            amount: Uint128::new(4000000u128),
+        },
+    )
+    .unwrap();
+}
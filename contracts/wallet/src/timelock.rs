@@ -0,0 +1,112 @@
+use cosmwasm_std::{Binary, Coin, CosmosMsg, BankMsg, WasmMsg, Empty, Uint128, from_binary};
+use cw20::Cw20ExecuteMsg;
+
+use smartwallet::wallet::{ExecuteMsg, TimelockConfig, TimelockKind, TimelockedCommand};
+use crate::error::ContractError;
+
+pub fn validate_timelock(timelock: &TimelockConfig) -> Result<(), ContractError> {
+
+    if timelock.delay == 0{
+        return Err(ContractError::InvalidTimelock{reason: String::from("delay must be non-zero")});
+    }
+
+    for (i, threshold) in timelock.thresholds.iter().enumerate(){
+        if timelock.thresholds[..i].iter().any(|x| x.denom == threshold.denom){
+            return Err(ContractError::InvalidTimelock{reason: format!("duplicate threshold for {}", threshold.denom)});
+        }
+    }
+
+    Ok(())
+}
+
+/// whether the multisig has to queue the command instead of running it right away
+pub fn requires_timelock(timelock: Option<&TimelockConfig>, command: &TimelockedCommand) -> bool {
+    let timelock = match timelock{
+        Some(timelock) => timelock,
+        None => return false,
+    };
+
+    match command{
+        TimelockedCommand::Execute{command} => {
+            timelock.kinds.contains(&command_kind(command)) || exceeds_threshold(timelock, &command_funds(command))
+        },
+        TimelockedCommand::ReplaceMultisig{..} => timelock.kinds.contains(&TimelockKind::ReplaceMultisig),
+        //otherwise the timelock could be switched off and bypassed in one go
+        TimelockedCommand::UpdateTimelock{..} => true,
+        //otherwise a hot wallet or template could move what the thresholds hold back
+        TimelockedCommand::UpdateConfig{..} => true,
+    }
+}
+
+/// config updates that widen what hot wallets can reach; queued while a timelock is set
+pub fn is_timelocked_config_update(msg: &ExecuteMsg) -> bool {
+    matches!(msg,
+        ExecuteMsg::UpsertHot{..}
+        | ExecuteMsg::ReplaceContractWhitelist{..}
+        | ExecuteMsg::AddWhitelistedContract{..}
+        | ExecuteMsg::UpdateWhitelistedContract{..}
+        | ExecuteMsg::AddActionTemplate{..}
+        | ExecuteMsg::UpdateActionTemplate{..}
+        | ExecuteMsg::UpsertSwapPair{..}
+        | ExecuteMsg::UpsertAirdrop{..})
+}
+
+pub fn command_kind(command: &CosmosMsg<Empty>) -> TimelockKind {
+    match command{
+        CosmosMsg::Bank(BankMsg::Send{..}) => TimelockKind::BankSend,
+        CosmosMsg::Wasm(WasmMsg::Execute{..}) => TimelockKind::WasmExecute,
+        CosmosMsg::Wasm(WasmMsg::Instantiate{..}) => TimelockKind::WasmInstantiate,
+        CosmosMsg::Wasm(WasmMsg::Migrate{..}) => TimelockKind::WasmMigrate,
+        CosmosMsg::Wasm(WasmMsg::UpdateAdmin{..}) | CosmosMsg::Wasm(WasmMsg::ClearAdmin{..}) => TimelockKind::WasmAdmin,
+        _ => TimelockKind::Other,
+    }
+}
+
+/// the account or contract the command acts on; none for instantiates and other msgs
+pub fn command_target(command: &CosmosMsg<Empty>) -> Option<String> {
+    match command{
+        CosmosMsg::Bank(BankMsg::Send{to_address, ..}) => Some(to_address.clone()),
+        CosmosMsg::Wasm(WasmMsg::Execute{contract_addr, ..})
+        | CosmosMsg::Wasm(WasmMsg::Migrate{contract_addr, ..})
+        | CosmosMsg::Wasm(WasmMsg::UpdateAdmin{contract_addr, ..})
+        | CosmosMsg::Wasm(WasmMsg::ClearAdmin{contract_addr}) => Some(contract_addr.clone()),
+        _ => None,
+    }
+}
+
+/// native coins leaving the wallet with the command, plus cw20 amounts moved by it with the
+/// token contract address as denom
+pub fn command_funds(command: &CosmosMsg<Empty>) -> Vec<Coin> {
+    match command{
+        CosmosMsg::Bank(BankMsg::Send{amount, ..}) => amount.clone(),
+        CosmosMsg::Bank(BankMsg::Burn{amount}) => amount.clone(),
+        CosmosMsg::Wasm(WasmMsg::Execute{contract_addr, msg, funds}) => {
+            let mut coins = funds.clone();
+            if let Some(amount) = cw20_amount(msg){
+                coins.push(Coin{denom: contract_addr.clone(), amount});
+            }
+            coins
+        },
+        CosmosMsg::Wasm(WasmMsg::Instantiate{funds, ..}) => funds.clone(),
+        _ => vec![],
+    }
+}
+
+pub fn cw20_amount(msg: &Binary) -> Option<Uint128> {
+    match from_binary::<Cw20ExecuteMsg>(msg).ok()?{
+        Cw20ExecuteMsg::Transfer{amount, ..}
+        | Cw20ExecuteMsg::TransferFrom{amount, ..}
+        | Cw20ExecuteMsg::Send{amount, ..}
+        | Cw20ExecuteMsg::SendFrom{amount, ..}
+        | Cw20ExecuteMsg::Burn{amount}
+        | Cw20ExecuteMsg::BurnFrom{amount, ..}
+        | Cw20ExecuteMsg::IncreaseAllowance{amount, ..} => Some(amount),
+        _ => None,
+    }
+}
+
+fn exceeds_threshold(timelock: &TimelockConfig, funds: &[Coin]) -> bool {
+    funds.iter().any(|coin| {
+        timelock.thresholds.iter().any(|x| x.denom == coin.denom && coin.amount >= x.amount)
+    })
+}
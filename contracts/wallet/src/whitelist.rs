@@ -0,0 +1,71 @@
+use cosmwasm_std::{Deps, Order, QuerierWrapper, StdResult};
+use terra_cosmwasm::TerraQuerier;
+
+use smartwallet::wallet::{ContractDrift, WhitelistedContract};
+use crate::state::whitelisted_contracts;
+use crate::error::ContractError;
+
+/// code id the contract at address runs, from terra's wasm module
+pub fn query_code_id(querier: &QuerierWrapper, address: &str) -> StdResult<u64> {
+    Ok(TerraQuerier::new(querier).query_contract_info(address)?.code_id)
+}
+
+/// the contract has to still run the code id it was approved with; an admin migration changes it
+pub fn assert_code_id(querier: &QuerierWrapper, contract: &WhitelistedContract) -> Result<(), ContractError> {
+    let code_id = query_code_id(querier, &contract.address)?;
+    if code_id != contract.code_id{
+        return Err(ContractError::CodeIdMismatch{address: contract.address.clone(), expected: contract.code_id, actual: code_id});
+    }
+    Ok(())
+}
+
+/// address has to be a deployed contract running code_id
+pub fn validate_whitelisted_contract(deps: Deps, contract: &WhitelistedContract) -> Result<(), ContractError> {
+    deps.api.addr_validate(&contract.address)?;
+    assert_code_id(&deps.querier, contract)
+}
+
+pub fn validate_whitelisted_contracts(deps: Deps, contracts: &[WhitelistedContract]) -> Result<(), ContractError> {
+    for (i, contract) in contracts.iter().enumerate(){
+        if contracts[..i].iter().any(|x| x.label == contract.label){
+            return Err(ContractError::DuplicateContractLabel{label: contract.label.clone()});
+        }
+        validate_whitelisted_contract(deps, contract)?;
+    }
+    Ok(())
+}
+
+/// whitelisted contract a hot msg may dispatch to, checked against the deployed code id
+pub fn load_whitelisted_contract(deps: Deps, label: &str) -> Result<WhitelistedContract, ContractError> {
+    let contract = whitelisted_contracts()
+        .may_load(deps.storage, label)?
+        .ok_or(ContractError::ContractNotWhitelisted{})?;
+    assert_code_id(&deps.querier, &contract)?;
+    Ok(contract)
+}
+
+/// same as load_whitelisted_contract, for a contract known by address (e.g. a cw20 swap asset)
+pub fn load_whitelisted_address(deps: Deps, address: &str) -> Result<WhitelistedContract, ContractError> {
+    let label = whitelisted_contracts()
+        .idx
+        .address
+        .prefix(address.as_bytes().to_vec())
+        .range(deps.storage, None, None, Order::Ascending)
+        .next()
+        .transpose()?
+        .map(|(_, contract)| contract.label)
+        .ok_or(ContractError::ContractNotWhitelisted{})?;
+    load_whitelisted_contract(deps, &label)
+}
+
+/// a contract whose info can't be queried counts as drifted
+pub fn contract_drift(querier: &QuerierWrapper, contract: WhitelistedContract) -> ContractDrift {
+    let live_code_id = query_code_id(querier, &contract.address).ok();
+    ContractDrift{
+        drifted: live_code_id != Some(contract.code_id),
+        live_code_id,
+        label: contract.label,
+        address: contract.address,
+        code_id: contract.code_id,
+    }
+}
@@ -0,0 +1,2816 @@
+//! deploys the wallet next to a real cw3 fixed multisig and stand-in anchor market / bluna reward /
+//! terraswap pair contracts, then drives every ExecuteMsg through the hot wallet or the multisig
+use anyhow::Result as AnyResult;
+use basset::reward::{
+    AccruedRewardsResponse, ExecuteMsg as RewardExecuteMsg, QueryMsg as RewardQueryMsg,
+};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi};
+use cosmwasm_std::{
+    coins, to_binary, Addr, Api, BankMsg, Binary, BlockInfo, CanonicalAddr, Coin, ContractResult,
+    CosmosMsg, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, RecoverPubkeyError, Reply,
+    Response, StdError, StdResult, Storage, Uint128, VerificationError, WasmMsg,
+};
+use cw20::{
+    BalanceResponse as Cw20BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg,
+};
+use cw3::Vote;
+use cw3_fixed_multisig::msg::{ExecuteMsg as Cw3ExecuteMsg, QueryMsg as Cw3QueryMsg};
+use cw_multi_test::{
+    App, AppBuilder, AppResponse, Contract, ContractWrapper, CustomHandler, Executor, WasmKeeper,
+};
+use moneymarket::market::ExecuteMsg as MarketExecuteMsg;
+use smartwallet::wallet::{
+    ActionFunds, ActionHistoryResponse, ActionLimit, ActionParam, ActionParamSlot, ActionParamType,
+    ActionStateResponse, ActionTemplate, ActionTemplateResponse, ActorRole, AssetInfo,
+    BeneficiariesResponse, Beneficiary, BeneficiaryPolicy, BeneficiaryPolicyResponse,
+    ConfigResponse, ContractDrift, Cw20Balance, Cw20BalancesResponse, ExecuteMsg, FreezeInfo,
+    FreezeResponse, GasTank, GuardiansResponse, HotWallet, HotWalletsResponse, InstantiateMsg,
+    MultiSigVoter, PendingBeneficiaryPolicy, QueryMsg, QueuedCommandsResponse, RecoveryProposal,
+    RecoveryProposalsResponse, ReservesResponse, SpendingAllowanceResponse, SpendingLimit,
+    SwapPair, TimelockConfig, TimelockKind, TimelockResponse, TimelockedCommand,
+    WhitelistDriftResponse, WhitelistedContract,
+};
+use smartwallet_wallet::contract::DEFAULT_UUSD_RESERVE;
+use smartwallet_wallet::error::ContractError;
+use smartwallet_wallet::swap::PairExecuteMsg;
+use terra_cosmwasm::{
+    ContractInfoResponse, TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper,
+};
+
+const OWNER: &str = "owner";
+const VOTER1: &str = "voter1";
+const VOTER2: &str = "voter2";
+const HOT: &str = "hot";
+const STRANGER: &str = "stranger";
+const GUARDIANS: [&str; 3] = ["guardian1", "guardian2", "guardian3"];
+const RECOVERY_DELAY: u64 = 86400u64;
+
+const WALLET_FUNDS: u128 = 1_000_000_000u128;
+const REWARD_FUNDS: u128 = 50_000_000u128;
+const BLUNA_REWARDS: u128 = 2_000_000u128;
+const GAS_TANK_MAX: u128 = 5_000_000u128;
+const GAS_COOLDOWN: u64 = 3600u64;
+
+type TerraApp = App<Empty, TerraQueryWrapper>;
+
+/// answers the treasury queries deduct_tax makes; 0.1% rate, 1 UST cap
+struct TerraMock;
+
+impl CustomHandler<Empty, TerraQueryWrapper> for TerraMock {
+    fn execute(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _block: &BlockInfo,
+        sender: Addr,
+        msg: Empty,
+    ) -> AnyResult<AppResponse> {
+        panic!("unexpected custom msg {:?} from {}", msg, sender)
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        storage: &dyn Storage,
+        _block: &BlockInfo,
+        msg: TerraQueryWrapper,
+    ) -> AnyResult<Binary> {
+        match msg.query_data {
+            TerraQuery::TaxRate {} => Ok(to_binary(&TaxRateResponse {
+                rate: Decimal::permille(1),
+            })?),
+            TerraQuery::TaxCap { .. } => Ok(to_binary(&TaxCapResponse {
+                cap: Uint128::new(1_000_000u128),
+            })?),
+            // the wasm keeper's records are in the same storage
+            TerraQuery::ContractInfo { contract_address } => {
+                let contract = WasmKeeper::<Empty, TerraQueryWrapper>::new()
+                    .load_contract(storage, &Addr::unchecked(&contract_address))?;
+                Ok(to_binary(&ContractInfoResponse {
+                    address: contract_address,
+                    creator: contract.creator.to_string(),
+                    code_id: contract.code_id as u64,
+                    admin: contract.admin.map(|x| x.to_string()),
+                })?)
+            }
+            query => panic!("unexpected terra query {:?}", query),
+        }
+    }
+}
+
+/// multi-test names contracts "Contract #n", which MockApi refuses to validate
+struct TestApi(MockApi);
+
+impl Api for TestApi {
+    fn addr_validate(&self, human: &str) -> StdResult<Addr> {
+        if human.starts_with("Contract #") {
+            return Ok(Addr::unchecked(human));
+        }
+        self.0.addr_validate(human)
+    }
+
+    fn addr_canonicalize(&self, human: &str) -> StdResult<CanonicalAddr> {
+        self.0.addr_canonicalize(human)
+    }
+
+    fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+        self.0.addr_humanize(canonical)
+    }
+
+    fn secp256k1_verify(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        self.0.secp256k1_verify(message_hash, signature, public_key)
+    }
+
+    fn secp256k1_recover_pubkey(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        recovery_param: u8,
+    ) -> Result<Vec<u8>, RecoverPubkeyError> {
+        self.0
+            .secp256k1_recover_pubkey(message_hash, signature, recovery_param)
+    }
+
+    fn ed25519_verify(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        self.0.ed25519_verify(message, signature, public_key)
+    }
+
+    fn ed25519_batch_verify(
+        &self,
+        messages: &[&[u8]],
+        signatures: &[&[u8]],
+        public_keys: &[&[u8]],
+    ) -> Result<bool, VerificationError> {
+        self.0
+            .ed25519_batch_verify(messages, signatures, public_keys)
+    }
+
+    fn debug(&self, message: &str) {
+        self.0.debug(message)
+    }
+}
+
+// stand-in anchor market; keeps whatever it is sent
+fn market_execute(
+    _deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: MarketExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        MarketExecuteMsg::DepositStable {} => {
+            Ok(Response::new().add_attribute("action", "deposit_stable"))
+        }
+        MarketExecuteMsg::RepayStable {} => {
+            Ok(Response::new().add_attribute("action", "repay_stable"))
+        }
+        MarketExecuteMsg::BorrowStable { borrow_amount, to } => {
+            Ok(Response::new().add_message(BankMsg::Send {
+                to_address: to.unwrap_or_else(|| info.sender.to_string()),
+                amount: coins(borrow_amount.into(), "uusd"),
+            }))
+        }
+        _ => Err(StdError::generic_err("not implemented")),
+    }
+}
+
+// stand-in bluna reward contract; pays a fixed reward to the claimer
+fn reward_execute(
+    _deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: RewardExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        RewardExecuteMsg::ClaimRewards { recipient } => {
+            Ok(Response::new().add_message(BankMsg::Send {
+                to_address: recipient.unwrap_or_else(|| info.sender.to_string()),
+                amount: coins(BLUNA_REWARDS, "uusd"),
+            }))
+        }
+        _ => Err(StdError::generic_err("not implemented")),
+    }
+}
+
+// stand-in terraswap uusd/uluna pair; pays 1 uluna per 10 uusd offered
+fn pair_execute(
+    _deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: PairExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        PairExecuteMsg::Swap { offer_asset, .. } => {
+            Ok(Response::new().add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(offer_asset.amount.u128() / 10, "uluna"),
+            }))
+        }
+    }
+}
+
+fn reward_query(_deps: Deps, _env: Env, msg: RewardQueryMsg) -> StdResult<Binary> {
+    match msg {
+        RewardQueryMsg::AccruedRewards { .. } => to_binary(&AccruedRewardsResponse {
+            rewards: Uint128::new(BLUNA_REWARDS),
+        }),
+        _ => Err(StdError::generic_err("not implemented")),
+    }
+}
+
+fn stand_in_instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn stand_in_query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Ok(Binary::default())
+}
+
+fn stand_in_migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn contract_wallet() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            smartwallet_wallet::contract::execute,
+            smartwallet_wallet::contract::instantiate,
+            smartwallet_wallet::contract::query,
+        )
+        .with_reply(smartwallet_wallet::contract::reply)
+        .with_migrate(smartwallet_wallet::contract::migrate),
+    )
+}
+
+fn contract_cw3() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw3_fixed_multisig::contract::execute,
+        cw3_fixed_multisig::contract::instantiate,
+        cw3_fixed_multisig::contract::query,
+    ))
+}
+
+fn contract_market() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(market_execute, stand_in_instantiate, stand_in_query)
+            .with_migrate(stand_in_migrate),
+    )
+}
+
+fn contract_reward() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        reward_execute,
+        stand_in_instantiate,
+        reward_query,
+    ))
+}
+
+fn contract_cw20() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    ))
+}
+
+fn contract_pair() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        pair_execute,
+        stand_in_instantiate,
+        stand_in_query,
+    ))
+}
+
+struct Suite {
+    app: TerraApp,
+    wallet: Addr,
+    multisig: Addr,
+    market: Addr,
+    reward: Addr,
+    market_code_id: u64,
+    reward_code_id: u64,
+}
+
+impl Suite {
+    fn hot_wallet(&self, whitelisted_messages: Vec<u64>) -> HotWallet {
+        HotWallet {
+            address: String::from(HOT),
+            label: String::from("bot"),
+            gas_cooldown: GAS_COOLDOWN,
+            gas_tanks: vec![uusd_gas_tank()],
+            whitelisted_messages,
+            spending_limits: vec![],
+            action_limits: vec![],
+        }
+    }
+
+    fn whitelist(&self) -> Vec<WhitelistedContract> {
+        vec![
+            WhitelistedContract {
+                address: self.market.to_string(),
+                label: String::from("anchor_market"),
+                code_id: self.market_code_id,
+            },
+            WhitelistedContract {
+                address: self.reward.to_string(),
+                label: String::from("bluna_reward"),
+                code_id: self.reward_code_id,
+            },
+        ]
+    }
+
+    fn hot(&mut self, msg: &ExecuteMsg) -> AnyResult<AppResponse> {
+        self.execute_as(HOT, msg)
+    }
+
+    fn execute_as(&mut self, sender: &str, msg: &ExecuteMsg) -> AnyResult<AppResponse> {
+        let wallet = self.wallet.clone();
+        self.app
+            .execute_contract(Addr::unchecked(sender), wallet, msg, &[])
+    }
+
+    /// propose, vote and execute msg through the 2-of-2 multisig
+    fn multisig(&mut self, msg: &ExecuteMsg) -> AnyResult<AppResponse> {
+        let wallet_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.wallet.to_string(),
+            msg: to_binary(msg)?,
+            funds: vec![],
+        });
+        self.cw3_proposal(vec![wallet_msg])
+    }
+
+    fn cw3_proposal(&mut self, msgs: Vec<CosmosMsg>) -> AnyResult<AppResponse> {
+        let multisig = self.multisig.clone();
+
+        let res = self.app.execute_contract(
+            Addr::unchecked(VOTER1),
+            multisig.clone(),
+            &Cw3ExecuteMsg::Propose {
+                title: String::from("wallet"),
+                description: String::from("wallet msg"),
+                msgs,
+                latest: None,
+            },
+            &[],
+        )?;
+        let proposal_id: u64 = res
+            .events
+            .iter()
+            .flat_map(|x| x.attributes.iter())
+            .find(|x| x.key == "proposal_id")
+            .unwrap()
+            .value
+            .parse()
+            .unwrap();
+
+        self.app.execute_contract(
+            Addr::unchecked(VOTER2),
+            multisig.clone(),
+            &Cw3ExecuteMsg::Vote {
+                proposal_id,
+                vote: Vote::Yes,
+            },
+            &[],
+        )?;
+
+        self.app.execute_contract(
+            Addr::unchecked(VOTER1),
+            multisig,
+            &Cw3ExecuteMsg::Execute { proposal_id },
+            &[],
+        )
+    }
+
+    fn balance(&self, address: &Addr) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(address, "uusd")
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    fn query<T: serde::de::DeserializeOwned>(&self, msg: &QueryMsg) -> StdResult<T> {
+        self.app.wrap().query_wasm_smart(&self.wallet, msg)
+    }
+
+    fn advance_time(&mut self, seconds: u64) {
+        self.app.update_block(|block| {
+            block.time = block.time.plus_seconds(seconds);
+            block.height += seconds / 5;
+        });
+    }
+}
+
+fn setup() -> Suite {
+    let mut app: TerraApp = AppBuilder::new()
+        .with_api(TestApi(MockApi::default()))
+        .with_custom(TerraMock)
+        .build();
+
+    let wallet_code_id = app.store_code(contract_wallet());
+    let cw3_code_id = app.store_code(contract_cw3());
+    let market_code_id = app.store_code(contract_market());
+    let reward_code_id = app.store_code(contract_reward());
+
+    let market = app
+        .instantiate_contract(
+            market_code_id,
+            Addr::unchecked(OWNER),
+            &Empty {},
+            &[],
+            "market",
+            Some(String::from(OWNER)),
+        )
+        .unwrap();
+    let reward = app
+        .instantiate_contract(
+            reward_code_id,
+            Addr::unchecked(OWNER),
+            &Empty {},
+            &[],
+            "reward",
+            None,
+        )
+        .unwrap();
+
+    let whitelisted_contracts = vec![
+        WhitelistedContract {
+            address: market.to_string(),
+            label: String::from("anchor_market"),
+            code_id: market_code_id,
+        },
+        WhitelistedContract {
+            address: reward.to_string(),
+            label: String::from("bluna_reward"),
+            code_id: reward_code_id,
+        },
+    ];
+
+    let wallet = app
+        .instantiate_contract(
+            wallet_code_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg::SpawnMultiSig {
+                hot_wallets: vec![HotWallet {
+                    address: String::from(HOT),
+                    label: String::from("bot"),
+                    gas_cooldown: GAS_COOLDOWN,
+                    gas_tanks: vec![uusd_gas_tank()],
+                    whitelisted_messages: vec![0, 1, 2],
+                    spending_limits: vec![],
+                    action_limits: vec![],
+                }],
+                whitelisted_contracts,
+                max_voting_period_in_blocks: 100,
+                required_weight: 2,
+                multisig_voters: vec![
+                    MultiSigVoter {
+                        addr: String::from(VOTER1),
+                        weight: 1,
+                    },
+                    MultiSigVoter {
+                        addr: String::from(VOTER2),
+                        weight: 1,
+                    },
+                ],
+                cw3_code_id,
+            },
+            &[],
+            "smart wallet",
+            None,
+        )
+        .unwrap();
+
+    app.init_bank_balance(&wallet, coins(WALLET_FUNDS, "uusd"))
+        .unwrap();
+    app.init_bank_balance(&reward, coins(REWARD_FUNDS, "uusd"))
+        .unwrap();
+
+    let config: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&wallet, &QueryMsg::Config {})
+        .unwrap();
+
+    Suite {
+        app,
+        wallet,
+        multisig: config.cw3_address,
+        market,
+        reward,
+        market_code_id,
+        reward_code_id,
+    }
+}
+
+/// multi-test only keeps the rendered error, and wraps it again when it bubbles up
+/// through the multisig
+fn assert_contract_error(err: anyhow::Error, expected: ContractError) {
+    let expected = expected.to_string();
+    assert!(
+        err.chain().any(|x| x.to_string() == expected),
+        "expected {:?}, got {:?}",
+        expected,
+        err
+    );
+}
+
+fn uusd_gas_tank() -> GasTank {
+    GasTank {
+        denom: String::from("uusd"),
+        max: Uint128::new(GAS_TANK_MAX),
+        buffer: Uint128::from(DEFAULT_UUSD_RESERVE),
+    }
+}
+
+fn uusd(amount: u128) -> Vec<Coin> {
+    coins(amount, "uusd")
+}
+
+fn deposit_template(max_amount: u128) -> ActionTemplate {
+    ActionTemplate {
+        label: String::from("earn"),
+        contract_label: String::from("anchor_market"),
+        msg: String::from(r#"{"deposit_stable":{}}"#),
+        params: vec![ActionParamSlot {
+            name: String::from("amount"),
+            param_type: ActionParamType::Uint128,
+        }],
+        funds: Some(ActionFunds {
+            denom: String::from("uusd"),
+            max_amount: Uint128::new(max_amount),
+            amount_param: String::from("amount"),
+        }),
+    }
+}
+
+fn amount_param(amount: u128) -> Vec<ActionParam> {
+    vec![ActionParam {
+        name: String::from("amount"),
+        value: amount.to_string(),
+    }]
+}
+
+#[test]
+fn spawn_multisig_records_cw3_address() {
+    let suite = setup();
+
+    // the reply parsed the cw3 address out of MsgInstantiateContractResponse
+    let threshold: cw3::ThresholdResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.multisig, &Cw3QueryMsg::Threshold {})
+        .unwrap();
+    assert_eq!(
+        threshold,
+        cw3::ThresholdResponse::AbsoluteCount {
+            weight: 2,
+            total_weight: 2
+        }
+    );
+
+    let res: HotWalletsResponse = suite
+        .query(&QueryMsg::HotWallets {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(res.hot_wallets, vec![suite.hot_wallet(vec![0, 1, 2])]);
+}
+
+#[test]
+fn invalid_reply_id() {
+    let mut deps = mock_dependencies(&[]);
+    let err = smartwallet_wallet::contract::reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: u64::MAX,
+            result: ContractResult::Err(String::from("nope")),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidReplyId {});
+}
+
+#[test]
+fn anchor_earn_deposit() {
+    let mut suite = setup();
+
+    suite
+        .hot(&ExecuteMsg::AnchorEarnDeposit {
+            amount: Uint128::new(100_000_000u128),
+        })
+        .unwrap();
+    assert_eq!(suite.balance(&suite.market), 100_000_000u128);
+
+    // capped so the uusd reserve stays behind
+    suite
+        .hot(&ExecuteMsg::AnchorEarnDeposit {
+            amount: Uint128::new(WALLET_FUNDS),
+        })
+        .unwrap();
+    assert_eq!(suite.balance(&suite.wallet), DEFAULT_UUSD_RESERVE);
+
+    let err = suite
+        .hot(&ExecuteMsg::AnchorEarnDeposit {
+            amount: Uint128::new(1u128),
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::ReserveBreached {
+            denom: String::from("uusd"),
+            reserve: Uint128::new(DEFAULT_UUSD_RESERVE),
+        },
+    );
+}
+
+// regression: the deposit used to be sized from the hot wallet's balance
+#[test]
+fn anchor_earn_deposit_spends_smart_wallet_balance() {
+    let mut suite = setup();
+
+    // a well funded hot wallet doesn't let the smart wallet dip into its reserve
+    suite
+        .app
+        .init_bank_balance(&Addr::unchecked(HOT), uusd(WALLET_FUNDS))
+        .unwrap();
+    suite
+        .app
+        .init_bank_balance(&suite.wallet.clone(), uusd(DEFAULT_UUSD_RESERVE))
+        .unwrap();
+    let err = suite
+        .hot(&ExecuteMsg::AnchorEarnDeposit {
+            amount: Uint128::new(1u128),
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::ReserveBreached {
+            denom: String::from("uusd"),
+            reserve: Uint128::new(DEFAULT_UUSD_RESERVE),
+        },
+    );
+
+    // an empty hot wallet doesn't stop the smart wallet from depositing
+    suite
+        .app
+        .init_bank_balance(&Addr::unchecked(HOT), vec![])
+        .unwrap();
+    suite
+        .app
+        .init_bank_balance(&suite.wallet.clone(), uusd(DEFAULT_UUSD_RESERVE + 1))
+        .unwrap();
+    suite
+        .hot(&ExecuteMsg::AnchorEarnDeposit {
+            amount: Uint128::new(WALLET_FUNDS),
+        })
+        .unwrap();
+    assert_eq!(suite.balance(&suite.market), 1u128);
+    assert_eq!(suite.balance(&suite.wallet), DEFAULT_UUSD_RESERVE);
+    assert_eq!(suite.balance(&Addr::unchecked(HOT)), 0u128);
+}
+
+#[test]
+fn bluna_claim() {
+    let mut suite = setup();
+
+    suite.hot(&ExecuteMsg::BlunaClaim {}).unwrap();
+    assert_eq!(suite.balance(&suite.wallet), WALLET_FUNDS + BLUNA_REWARDS);
+    assert_eq!(suite.balance(&suite.reward), REWARD_FUNDS - BLUNA_REWARDS);
+}
+
+#[test]
+fn bluna_compound() {
+    let mut suite = setup();
+    let hot_wallet = suite.hot_wallet(vec![11]);
+    suite
+        .multisig(&ExecuteMsg::UpsertHot { hot_wallet })
+        .unwrap();
+
+    suite.hot(&ExecuteMsg::BlunaCompound {}).unwrap();
+
+    // the reply deposits the claimed rewards, less the 0.1% tax
+    let deposit = BLUNA_REWARDS * 1000 / 1001;
+    assert_eq!(suite.balance(&suite.market), deposit);
+    assert_eq!(
+        suite.balance(&suite.wallet),
+        WALLET_FUNDS + BLUNA_REWARDS - deposit
+    );
+
+    // 1 UST short of the reserve, half the rewards go to topping it up
+    suite
+        .app
+        .init_bank_balance(
+            &suite.wallet.clone(),
+            uusd(DEFAULT_UUSD_RESERVE - 1_000_000u128),
+        )
+        .unwrap();
+    let market_balance = suite.balance(&suite.market);
+
+    suite.hot(&ExecuteMsg::BlunaCompound {}).unwrap();
+    let deposit = 1_000_000u128 * 1000 / 1001;
+    assert_eq!(suite.balance(&suite.market), market_balance + deposit);
+    assert_eq!(
+        suite.balance(&suite.wallet),
+        DEFAULT_UUSD_RESERVE + 1_000_000u128 - deposit
+    );
+}
+
+#[test]
+fn swap_checks_min_receive() {
+    let mut suite = setup();
+
+    let pair_code_id = suite.app.store_code(contract_pair());
+    let pair = suite
+        .app
+        .instantiate_contract(
+            pair_code_id,
+            Addr::unchecked(OWNER),
+            &Empty {},
+            &[],
+            "pair",
+            None,
+        )
+        .unwrap();
+    suite
+        .app
+        .init_bank_balance(&pair, coins(100_000_000u128, "uluna"))
+        .unwrap();
+
+    let mut whitelisted_contracts = suite.whitelist();
+    whitelisted_contracts.push(WhitelistedContract {
+        address: pair.to_string(),
+        label: String::from("terraswap_uusd_uluna"),
+        code_id: pair_code_id,
+    });
+    suite
+        .multisig(&ExecuteMsg::ReplaceContractWhitelist {
+            whitelisted_contracts,
+        })
+        .unwrap();
+    suite
+        .multisig(&ExecuteMsg::UpsertSwapPair {
+            swap_pair: SwapPair {
+                label: String::from("ust_luna"),
+                contract_label: String::from("terraswap_uusd_uluna"),
+                offer_asset: AssetInfo::NativeToken {
+                    denom: String::from("uusd"),
+                },
+                ask_asset: AssetInfo::NativeToken {
+                    denom: String::from("uluna"),
+                },
+                max_spread: Decimal::percent(2),
+                min_belief_price: Decimal::percent(500),
+                max_belief_price: Decimal::percent(2000),
+            },
+        })
+        .unwrap();
+    let hot_wallet = suite.hot_wallet(vec![13]);
+    suite
+        .multisig(&ExecuteMsg::UpsertHot { hot_wallet })
+        .unwrap();
+
+    let swap = |max_spread: Decimal, min_receive: u128| ExecuteMsg::Swap {
+        pair: String::from("ust_luna"),
+        offer_amount: Uint128::new(100_000_000u128),
+        belief_price: Decimal::from_ratio(10u128, 1u128),
+        max_spread,
+        min_receive: Uint128::new(min_receive),
+    };
+
+    let err = suite
+        .hot(&swap(Decimal::percent(5), 10_000_000u128))
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::InvalidSwap {
+            reason: String::from("max_spread above the pair's 0.02"),
+        },
+    );
+
+    // the reply reverts the whole swap
+    let err = suite
+        .hot(&swap(Decimal::percent(1), 11_000_000u128))
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::SwapBelowMinimum {
+            received: Uint128::new(10_000_000u128),
+            min_receive: Uint128::new(11_000_000u128),
+        },
+    );
+    assert_eq!(suite.balance(&suite.wallet), WALLET_FUNDS);
+
+    // a min_receive of 0 still has to meet what belief_price and max_spread imply
+    let err = suite
+        .hot(&ExecuteMsg::Swap {
+            pair: String::from("ust_luna"),
+            offer_amount: Uint128::new(100_000_000u128),
+            belief_price: Decimal::from_ratio(9u128, 1u128),
+            max_spread: Decimal::percent(1),
+            min_receive: Uint128::zero(),
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::SwapBelowMinimum {
+            received: Uint128::new(10_000_000u128),
+            min_receive: Uint128::new(11_000_000u128),
+        },
+    );
+
+    suite.hot(&swap(Decimal::percent(1), 0u128)).unwrap();
+    assert_eq!(suite.balance(&pair), 100_000_000u128);
+    assert_eq!(
+        suite
+            .app
+            .wrap()
+            .query_balance(&suite.wallet, "uluna")
+            .unwrap()
+            .amount,
+        Uint128::new(10_000_000u128)
+    );
+
+    // the receipt is journaled under the hot wallet that swapped
+    let res: ActionHistoryResponse = suite
+        .query(&QueryMsg::ActionHistory {
+            hot_wallet: Some(String::from(HOT)),
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(
+        res.entries
+            .iter()
+            .map(|x| x.action.as_str())
+            .collect::<Vec<_>>(),
+        vec!["swap", "swap_received"]
+    );
+}
+
+#[test]
+fn cw20_custody() {
+    let mut suite = setup();
+
+    let cw20_code_id = suite.app.store_code(contract_cw20());
+    let token = suite
+        .app
+        .instantiate_contract(
+            cw20_code_id,
+            Addr::unchecked(OWNER),
+            &cw20_base::msg::InstantiateMsg {
+                name: String::from("mirror"),
+                symbol: String::from("MIR"),
+                decimals: 6,
+                initial_balances: vec![Cw20Coin {
+                    address: String::from(OWNER),
+                    amount: Uint128::new(1_000_000u128),
+                }],
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            "mir",
+            None,
+        )
+        .unwrap();
+
+    // anyone can call the receive hook, so it doesn't track the sender
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            token.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: suite.wallet.to_string(),
+                amount: Uint128::new(600_000u128),
+                msg: Binary::default(),
+            },
+            &[],
+        )
+        .unwrap();
+    suite
+        .execute_as(
+            STRANGER,
+            &ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: String::from(STRANGER),
+                amount: Uint128::new(1u128),
+                msg: Binary::default(),
+            }),
+        )
+        .unwrap();
+    let res: Cw20BalancesResponse = suite
+        .query(&QueryMsg::Cw20Balances {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(res.balances, vec![]);
+
+    // only tokens answering a cw20 balance query can be tracked
+    let err = suite
+        .execute_as(
+            HOT,
+            &ExecuteMsg::AddTrackedToken {
+                token: token.to_string(),
+            },
+        )
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+    suite
+        .multisig(&ExecuteMsg::AddTrackedToken {
+            token: suite.market.to_string(),
+        })
+        .unwrap_err();
+    suite
+        .multisig(&ExecuteMsg::AddTrackedToken {
+            token: token.to_string(),
+        })
+        .unwrap();
+
+    let res: Cw20BalancesResponse = suite
+        .query(&QueryMsg::Cw20Balances {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(
+        res.balances,
+        vec![Cw20Balance {
+            token: token.to_string(),
+            balance: Uint128::new(600_000u128),
+        }]
+    );
+
+    let withdraw = ExecuteMsg::WithdrawCw20 {
+        token: token.to_string(),
+        amount: Uint128::new(250_000u128),
+        recipient: String::from(STRANGER),
+    };
+    let err = suite.hot(&withdraw).unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+
+    suite.multisig(&withdraw).unwrap();
+    let res: Cw20BalanceResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &token,
+            &Cw20QueryMsg::Balance {
+                address: String::from(STRANGER),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.balance, Uint128::new(250_000u128));
+
+    suite
+        .multisig(&ExecuteMsg::RemoveTrackedToken {
+            token: token.to_string(),
+        })
+        .unwrap();
+    let res: Cw20BalancesResponse = suite
+        .query(&QueryMsg::Cw20Balances {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(res.balances, vec![]);
+
+    let err = suite
+        .multisig(&ExecuteMsg::RemoveTrackedToken {
+            token: token.to_string(),
+        })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::TokenNotTracked {});
+}
+
+#[test]
+fn repay_stable() {
+    let mut suite = setup();
+
+    suite
+        .hot(&ExecuteMsg::RepayStable {
+            amount: Uint128::new(10_000_000u128),
+        })
+        .unwrap();
+    assert_eq!(suite.balance(&suite.market), 10_000_000u128);
+    assert_eq!(suite.balance(&suite.wallet), WALLET_FUNDS - 10_000_000u128);
+}
+
+#[test]
+fn fill_up_gas() {
+    let mut suite = setup();
+
+    suite.hot(&ExecuteMsg::FillUpGas {}).unwrap();
+
+    // 0.1% tax comes out of the refill
+    let tax = GAS_TANK_MAX - GAS_TANK_MAX * 1000 / 1001;
+    assert_eq!(suite.balance(&Addr::unchecked(HOT)), GAS_TANK_MAX - tax);
+    assert_eq!(
+        suite.balance(&suite.wallet),
+        WALLET_FUNDS - GAS_TANK_MAX + tax
+    );
+
+    let err = suite.hot(&ExecuteMsg::FillUpGas {}).unwrap_err();
+    assert_contract_error(err, ContractError::GasCooldown {});
+
+    // tank is topped up without spending anything
+    suite.advance_time(GAS_COOLDOWN);
+    suite
+        .app
+        .init_bank_balance(&Addr::unchecked(HOT), uusd(GAS_TANK_MAX))
+        .unwrap();
+    let err = suite.hot(&ExecuteMsg::FillUpGas {}).unwrap_err();
+    assert_contract_error(err, ContractError::GasTankFull {});
+
+    // smart wallet can't cover the refill on top of its reserve
+    suite
+        .app
+        .init_bank_balance(&Addr::unchecked(HOT), vec![])
+        .unwrap();
+    suite
+        .app
+        .init_bank_balance(&suite.wallet.clone(), uusd(DEFAULT_UUSD_RESERVE))
+        .unwrap();
+    let err = suite.hot(&ExecuteMsg::FillUpGas {}).unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::ReserveBreached {
+            denom: String::from("uusd"),
+            reserve: Uint128::new(DEFAULT_UUSD_RESERVE),
+        },
+    );
+}
+
+#[test]
+fn fill_up_gas_multi_denom() {
+    let mut suite = setup();
+
+    let mut hot_wallet = suite.hot_wallet(vec![]);
+    hot_wallet.gas_tanks.push(GasTank {
+        denom: String::from("uluna"),
+        max: Uint128::new(2_000_000u128),
+        buffer: Uint128::new(10_000_000u128),
+    });
+    suite
+        .multisig(&ExecuteMsg::UpsertHot {
+            hot_wallet: hot_wallet.clone(),
+        })
+        .unwrap();
+
+    // the uluna tank can't be funded on top of its buffer, so neither tank is
+    suite
+        .app
+        .init_bank_balance(
+            &suite.wallet.clone(),
+            vec![
+                Coin::new(WALLET_FUNDS, "uusd"),
+                Coin::new(11_000_000u128, "uluna"),
+            ],
+        )
+        .unwrap();
+    let err = suite.hot(&ExecuteMsg::FillUpGas {}).unwrap_err();
+    assert_contract_error(err, ContractError::SmartWalletGas {});
+
+    suite
+        .app
+        .init_bank_balance(
+            &suite.wallet.clone(),
+            vec![
+                Coin::new(WALLET_FUNDS, "uusd"),
+                Coin::new(12_000_000u128, "uluna"),
+            ],
+        )
+        .unwrap();
+    suite.hot(&ExecuteMsg::FillUpGas {}).unwrap();
+
+    // 0.1% tax comes out of each denom
+    let luna_tax = 2_000_000u128 - 2_000_000u128 * 1000 / 1001;
+    let luna = suite
+        .app
+        .wrap()
+        .query_balance(HOT, "uluna")
+        .unwrap()
+        .amount
+        .u128();
+    assert_eq!(luna, 2_000_000u128 - luna_tax);
+    let usd_tax = GAS_TANK_MAX - GAS_TANK_MAX * 1000 / 1001;
+    assert_eq!(suite.balance(&Addr::unchecked(HOT)), GAS_TANK_MAX - usd_tax);
+
+    // duplicate denoms are refused
+    hot_wallet.gas_tanks.push(uusd_gas_tank());
+    let err = suite
+        .multisig(&ExecuteMsg::UpsertHot { hot_wallet })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::InvalidGasTanks {
+            reason: String::from("duplicate gas tank for uusd"),
+        },
+    );
+}
+
+#[test]
+fn reserves() {
+    let mut suite = setup();
+    suite
+        .multisig(&ExecuteMsg::AddActionTemplate {
+            template: deposit_template(WALLET_FUNDS),
+        })
+        .unwrap();
+    let hot_wallet = suite.hot_wallet(vec![0, 2, 1000]);
+    suite
+        .multisig(&ExecuteMsg::UpsertHot { hot_wallet })
+        .unwrap();
+
+    let res: ReservesResponse = suite.query(&QueryMsg::Reserves {}).unwrap();
+    assert_eq!(res.reserves, uusd(DEFAULT_UUSD_RESERVE));
+
+    let err = suite
+        .execute_as(
+            HOT,
+            &ExecuteMsg::UpdateReserves {
+                reserves: uusd(0u128),
+            },
+        )
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+
+    let err = suite
+        .multisig(&ExecuteMsg::UpdateReserves {
+            reserves: vec![Coin::new(1u128, "uusd"), Coin::new(2u128, "uusd")],
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::InvalidReserves {
+            reason: String::from("duplicate reserve for uusd"),
+        },
+    );
+
+    // zero reserves are dropped
+    let reserve = 900_000_000u128;
+    suite
+        .multisig(&ExecuteMsg::UpdateReserves {
+            reserves: vec![Coin::new(reserve, "uusd"), Coin::new(0u128, "uluna")],
+        })
+        .unwrap();
+    let res: ReservesResponse = suite.query(&QueryMsg::Reserves {}).unwrap();
+    assert_eq!(res.reserves, uusd(reserve));
+
+    // every hot msg spending uusd has to leave the reserve behind
+    let breached = || ContractError::ReserveBreached {
+        denom: String::from("uusd"),
+        reserve: Uint128::new(reserve),
+    };
+    let err = suite
+        .hot(&ExecuteMsg::RepayStable {
+            amount: Uint128::new(WALLET_FUNDS - reserve + 1u128),
+        })
+        .unwrap_err();
+    assert_contract_error(err, breached());
+    let err = suite
+        .hot(&ExecuteMsg::RunAction {
+            id: 1000,
+            params: amount_param(WALLET_FUNDS - reserve + 1u128),
+        })
+        .unwrap_err();
+    assert_contract_error(err, breached());
+
+    suite
+        .hot(&ExecuteMsg::RunAction {
+            id: 1000,
+            params: amount_param(WALLET_FUNDS - reserve),
+        })
+        .unwrap();
+    assert_eq!(suite.balance(&suite.wallet), reserve);
+    let err = suite.hot(&ExecuteMsg::FillUpGas {}).unwrap_err();
+    assert_contract_error(err, breached());
+
+    // without reserves the whole balance can go
+    suite
+        .multisig(&ExecuteMsg::UpdateReserves { reserves: vec![] })
+        .unwrap();
+    let res: ReservesResponse = suite.query(&QueryMsg::Reserves {}).unwrap();
+    assert_eq!(res.reserves, vec![]);
+    suite
+        .hot(&ExecuteMsg::AnchorEarnDeposit {
+            amount: Uint128::new(WALLET_FUNDS),
+        })
+        .unwrap();
+    assert_eq!(suite.balance(&suite.wallet), 0u128);
+    assert_eq!(suite.balance(&suite.market), WALLET_FUNDS);
+}
+
+#[test]
+fn hot_wallet_checks() {
+    let mut suite = setup();
+
+    let wallet = suite.wallet.clone();
+    let err = suite
+        .app
+        .execute_contract(
+            Addr::unchecked(STRANGER),
+            wallet,
+            &ExecuteMsg::BlunaClaim {},
+            &[],
+        )
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+
+    let hot_wallet = suite.hot_wallet(vec![0]);
+    suite
+        .multisig(&ExecuteMsg::UpsertHot { hot_wallet })
+        .unwrap();
+
+    let err = suite.hot(&ExecuteMsg::BlunaClaim {}).unwrap_err();
+    assert_contract_error(err, ContractError::UnauthorizedAction {});
+}
+
+#[test]
+fn upsert_and_remove_hot() {
+    let mut suite = setup();
+
+    // only the multisig manages hot wallets
+    let err = suite
+        .hot(&ExecuteMsg::UpsertHot {
+            hot_wallet: suite.hot_wallet(vec![0, 1, 2, 3]),
+        })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+
+    let mut hot_wallet = suite.hot_wallet(vec![1]);
+    hot_wallet.address = String::from("hot2");
+    hot_wallet.label = String::from("keeper");
+    suite
+        .multisig(&ExecuteMsg::UpsertHot {
+            hot_wallet: hot_wallet.clone(),
+        })
+        .unwrap();
+
+    let res: HotWalletsResponse = suite
+        .query(&QueryMsg::HotWalletsByLabel {
+            label: String::from("keeper"),
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(res.hot_wallets, vec![hot_wallet]);
+
+    let res: HotWalletsResponse = suite
+        .query(&QueryMsg::HotWalletsByAction {
+            action_id: 1,
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(res.hot_wallets.len(), 2);
+
+    suite
+        .multisig(&ExecuteMsg::RemoveHot {
+            address: String::from(HOT),
+        })
+        .unwrap();
+    let err = suite.hot(&ExecuteMsg::BlunaClaim {}).unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+
+    let err = suite
+        .multisig(&ExecuteMsg::RemoveHot {
+            address: String::from(HOT),
+        })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::InvalidHotAddress {});
+
+    // address validation surfaces as a std error
+    let mut bad_wallet = suite.hot_wallet(vec![]);
+    bad_wallet.address = String::from("x");
+    let err = suite
+        .multisig(&ExecuteMsg::UpsertHot {
+            hot_wallet: bad_wallet,
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Invalid input: human address too short",
+        )),
+    );
+}
+
+#[test]
+fn replace_contract_whitelist() {
+    let mut suite = setup();
+
+    let reward_only = vec![suite.whitelist()[1].clone()];
+    suite
+        .multisig(&ExecuteMsg::ReplaceContractWhitelist {
+            whitelisted_contracts: reward_only,
+        })
+        .unwrap();
+
+    let err = suite
+        .hot(&ExecuteMsg::RepayStable {
+            amount: Uint128::new(1u128),
+        })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::ContractNotWhitelisted {});
+
+    suite.hot(&ExecuteMsg::BlunaClaim {}).unwrap();
+}
+
+#[test]
+fn whitelisted_contract_updates() {
+    let mut suite = setup();
+    let market = suite.whitelist()[0].clone();
+
+    let err = suite
+        .execute_as(
+            HOT,
+            &ExecuteMsg::RemoveWhitelistedContract {
+                label: market.label.clone(),
+            },
+        )
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+
+    let err = suite
+        .multisig(&ExecuteMsg::AddWhitelistedContract {
+            contract: market.clone(),
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::DuplicateContractLabel {
+            label: String::from("anchor_market"),
+        },
+    );
+
+    // the code id has to match what is deployed at the address
+    let mut market2 = WhitelistedContract {
+        address: suite.reward.to_string(),
+        label: String::from("anchor_market2"),
+        code_id: suite.market_code_id,
+    };
+    let err = suite
+        .multisig(&ExecuteMsg::AddWhitelistedContract {
+            contract: market2.clone(),
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::CodeIdMismatch {
+            address: suite.reward.to_string(),
+            expected: suite.market_code_id,
+            actual: suite.reward_code_id,
+        },
+    );
+
+    market2.address = suite.market.to_string();
+    suite
+        .multisig(&ExecuteMsg::AddWhitelistedContract {
+            contract: market2.clone(),
+        })
+        .unwrap();
+    let res: WhitelistedContract = suite
+        .query(&QueryMsg::WhitelistedContract {
+            label: String::from("anchor_market2"),
+        })
+        .unwrap();
+    assert_eq!(res, market2);
+
+    // addresses that are not deployed contracts are refused
+    let mut not_deployed = market.clone();
+    not_deployed.address = String::from(STRANGER);
+    suite
+        .multisig(&ExecuteMsg::UpdateWhitelistedContract {
+            contract: not_deployed,
+        })
+        .unwrap_err();
+
+    let mut unknown = market2.clone();
+    unknown.label = String::from("unknown");
+    let err = suite
+        .multisig(&ExecuteMsg::UpdateWhitelistedContract { contract: unknown })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::WhitelistedContractNotFound {});
+
+    // updates can repoint a label at another contract
+    let repointed = WhitelistedContract {
+        address: suite.reward.to_string(),
+        label: market.label.clone(),
+        code_id: suite.reward_code_id,
+    };
+    suite
+        .multisig(&ExecuteMsg::UpdateWhitelistedContract {
+            contract: repointed.clone(),
+        })
+        .unwrap();
+    let res: WhitelistedContract = suite
+        .query(&QueryMsg::WhitelistedContract {
+            label: market.label.clone(),
+        })
+        .unwrap();
+    assert_eq!(res, repointed);
+
+    suite
+        .multisig(&ExecuteMsg::RemoveWhitelistedContract {
+            label: market.label.clone(),
+        })
+        .unwrap();
+    let err = suite
+        .hot(&ExecuteMsg::RepayStable {
+            amount: Uint128::new(1u128),
+        })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::ContractNotWhitelisted {});
+    let err = suite
+        .multisig(&ExecuteMsg::RemoveWhitelistedContract {
+            label: market.label,
+        })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::WhitelistedContractNotFound {});
+
+    // replacing the whole list gets the same checks
+    let err = suite
+        .multisig(&ExecuteMsg::ReplaceContractWhitelist {
+            whitelisted_contracts: vec![market2.clone(), market2],
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::DuplicateContractLabel {
+            label: String::from("anchor_market2"),
+        },
+    );
+}
+
+#[test]
+fn whitelist_drift() {
+    let mut suite = setup();
+
+    let res: WhitelistDriftResponse = suite
+        .query(&QueryMsg::WhitelistDrift {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert!(res.contracts.iter().all(|x| !x.drifted));
+
+    // the market's admin migrates it to new code behind the wallet's back
+    let new_code_id = suite.app.store_code(contract_market());
+    suite
+        .app
+        .migrate_contract(
+            Addr::unchecked(OWNER),
+            suite.market.clone(),
+            &Empty {},
+            new_code_id,
+        )
+        .unwrap();
+
+    let err = suite
+        .hot(&ExecuteMsg::RepayStable {
+            amount: Uint128::new(1u128),
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::CodeIdMismatch {
+            address: suite.market.to_string(),
+            expected: suite.market_code_id,
+            actual: new_code_id,
+        },
+    );
+
+    let res: WhitelistDriftResponse = suite
+        .query(&QueryMsg::WhitelistDrift {
+            start_after: None,
+            limit: Some(1),
+        })
+        .unwrap();
+    assert_eq!(
+        res.contracts,
+        vec![ContractDrift {
+            label: String::from("anchor_market"),
+            address: suite.market.to_string(),
+            code_id: suite.market_code_id,
+            live_code_id: Some(new_code_id),
+            drifted: true,
+        }]
+    );
+    let res: WhitelistDriftResponse = suite
+        .query(&QueryMsg::WhitelistDrift {
+            start_after: Some(String::from("anchor_market")),
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(res.contracts.len(), 1);
+    assert!(!res.contracts[0].drifted);
+
+    // approving the new code id lets hot msgs through again
+    let mut market = suite.whitelist()[0].clone();
+    market.code_id = new_code_id;
+    suite
+        .multisig(&ExecuteMsg::UpdateWhitelistedContract { contract: market })
+        .unwrap();
+    suite
+        .hot(&ExecuteMsg::RepayStable {
+            amount: Uint128::new(1u128),
+        })
+        .unwrap();
+    let res: WhitelistDriftResponse = suite
+        .query(&QueryMsg::WhitelistDrift {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert!(res.contracts.iter().all(|x| !x.drifted));
+}
+
+#[test]
+fn replace_multisig_and_execute() {
+    let mut suite = setup();
+
+    suite
+        .multisig(&ExecuteMsg::Execute {
+            command: CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from(OWNER),
+                amount: uusd(1_000_000u128),
+            }),
+        })
+        .unwrap();
+    assert_eq!(suite.balance(&Addr::unchecked(OWNER)), 1_000_000u128);
+
+    let err = suite
+        .hot(&ExecuteMsg::ReplaceMultisig {
+            address: String::from(HOT),
+        })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+
+    suite
+        .multisig(&ExecuteMsg::ReplaceMultisig {
+            address: String::from(OWNER),
+        })
+        .unwrap();
+    let config: ConfigResponse = suite.query(&QueryMsg::Config {}).unwrap();
+    assert_eq!(config.cw3_address, Addr::unchecked(OWNER));
+
+    // the old multisig is locked out
+    let err = suite
+        .multisig(&ExecuteMsg::Execute {
+            command: CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from(OWNER),
+                amount: uusd(1u128),
+            }),
+        })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn action_templates() {
+    let mut suite = setup();
+
+    let mut bad_template = deposit_template(10_000_000u128);
+    bad_template.msg = String::from(r#"{"deposit_stable":"#);
+    let err = suite
+        .multisig(&ExecuteMsg::AddActionTemplate {
+            template: bad_template,
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::InvalidActionTemplate {
+            reason: String::from("msg is not valid json"),
+        },
+    );
+
+    suite
+        .multisig(&ExecuteMsg::AddActionTemplate {
+            template: deposit_template(10_000_000u128),
+        })
+        .unwrap();
+    let res: ActionTemplateResponse = suite.query(&QueryMsg::ActionTemplate { id: 1000 }).unwrap();
+    assert_eq!(res.template, deposit_template(10_000_000u128));
+
+    let hot_wallet = suite.hot_wallet(vec![1000, 1001]);
+    suite
+        .multisig(&ExecuteMsg::UpsertHot { hot_wallet })
+        .unwrap();
+
+    suite
+        .hot(&ExecuteMsg::RunAction {
+            id: 1000,
+            params: amount_param(5_000_000u128),
+        })
+        .unwrap();
+    assert_eq!(suite.balance(&suite.market), 5_000_000u128);
+
+    let err = suite
+        .hot(&ExecuteMsg::RunAction {
+            id: 1000,
+            params: amount_param(20_000_000u128),
+        })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::ActionFundsExceeded {});
+
+    let err = suite
+        .hot(&ExecuteMsg::RunAction {
+            id: 1000,
+            params: vec![],
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::InvalidActionParam {
+            name: String::from("amount"),
+        },
+    );
+
+    let err = suite
+        .hot(&ExecuteMsg::RunAction {
+            id: 1001,
+            params: vec![],
+        })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::ActionTemplateNotFound {});
+
+    // raise the cap in place
+    suite
+        .multisig(&ExecuteMsg::UpdateActionTemplate {
+            id: 1000,
+            template: deposit_template(50_000_000u128),
+        })
+        .unwrap();
+    suite
+        .hot(&ExecuteMsg::RunAction {
+            id: 1000,
+            params: amount_param(20_000_000u128),
+        })
+        .unwrap();
+    assert_eq!(suite.balance(&suite.market), 25_000_000u128);
+
+    suite
+        .multisig(&ExecuteMsg::RemoveActionTemplate { id: 1000 })
+        .unwrap();
+    let err = suite
+        .hot(&ExecuteMsg::RunAction {
+            id: 1000,
+            params: amount_param(1u128),
+        })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::ActionTemplateNotFound {});
+}
+
+#[test]
+fn spending_limits() {
+    let mut suite = setup();
+
+    let mut hot_wallet = suite.hot_wallet(vec![0, 1, 2]);
+    hot_wallet.spending_limits = vec![SpendingLimit {
+        denom: String::from("uusd"),
+        max_amount: Uint128::new(10_000_000u128),
+        window: 86400,
+    }];
+    suite
+        .multisig(&ExecuteMsg::UpsertHot { hot_wallet })
+        .unwrap();
+
+    suite
+        .hot(&ExecuteMsg::RepayStable {
+            amount: Uint128::new(8_000_000u128),
+        })
+        .unwrap();
+
+    let err = suite
+        .hot(&ExecuteMsg::AnchorEarnDeposit {
+            amount: Uint128::new(5_000_000u128),
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::SpendingLimitExceeded {
+            denom: String::from("uusd"),
+        },
+    );
+
+    let res: SpendingAllowanceResponse = suite
+        .query(&QueryMsg::SpendingAllowance {
+            address: String::from(HOT),
+        })
+        .unwrap();
+    assert_eq!(res.allowances[0].remaining, Uint128::new(2_000_000u128));
+
+    // the first spend rolls out of the window
+    suite.advance_time(86400);
+    suite
+        .hot(&ExecuteMsg::AnchorEarnDeposit {
+            amount: Uint128::new(5_000_000u128),
+        })
+        .unwrap();
+}
+
+#[test]
+fn action_limits() {
+    let mut suite = setup();
+
+    let mut hot_wallet = suite.hot_wallet(vec![0, 1, 2]);
+    hot_wallet.action_limits = vec![ActionLimit {
+        action_id: 1,
+        cooldown: 600,
+        max_calls: Some(2),
+        period: 3600,
+    }];
+    suite
+        .multisig(&ExecuteMsg::UpsertHot { hot_wallet })
+        .unwrap();
+
+    let now = suite.app.block_info().time.seconds();
+    suite.hot(&ExecuteMsg::BlunaClaim {}).unwrap();
+
+    let err = suite.hot(&ExecuteMsg::BlunaClaim {}).unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::ActionRateLimited {
+            next_allowed: now + 600,
+        },
+    );
+
+    suite.advance_time(600);
+    suite.hot(&ExecuteMsg::BlunaClaim {}).unwrap();
+
+    // out of calls for this period
+    let res: ActionStateResponse = suite
+        .query(&QueryMsg::ActionState {
+            address: String::from(HOT),
+            action_id: 1,
+        })
+        .unwrap();
+    assert_eq!(res.period_calls, 2);
+    assert_eq!(res.next_allowed, now + 3600);
+}
+
+fn setup_guardians() -> Suite {
+    let mut suite = setup();
+    suite
+        .multisig(&ExecuteMsg::UpdateGuardians {
+            guardians: GUARDIANS.iter().map(|x| x.to_string()).collect(),
+            threshold: 2,
+            delay: RECOVERY_DELAY,
+        })
+        .unwrap();
+    suite
+}
+
+#[test]
+fn guardian_recovery() {
+    let mut suite = setup_guardians();
+
+    let res: GuardiansResponse = suite.query(&QueryMsg::Guardians {}).unwrap();
+    assert_eq!(res.threshold, 2);
+    assert_eq!(res.guardians.len(), 3);
+
+    let err = suite
+        .execute_as(
+            STRANGER,
+            &ExecuteMsg::ProposeRecovery {
+                new_cw3_address: String::from(OWNER),
+            },
+        )
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+
+    suite
+        .execute_as(
+            GUARDIANS[0],
+            &ExecuteMsg::ProposeRecovery {
+                new_cw3_address: String::from(OWNER),
+            },
+        )
+        .unwrap();
+
+    let err = suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteRecovery { id: 0 })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::RecoveryNotApproved {});
+
+    let err = suite
+        .execute_as(GUARDIANS[0], &ExecuteMsg::ApproveRecovery { id: 0 })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::RecoveryAlreadyApproved {});
+
+    let now = suite.app.block_info().time.seconds();
+    suite
+        .execute_as(GUARDIANS[1], &ExecuteMsg::ApproveRecovery { id: 0 })
+        .unwrap();
+
+    let res: RecoveryProposalsResponse = suite
+        .query(&QueryMsg::RecoveryProposals {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(
+        res.proposals,
+        vec![RecoveryProposal {
+            id: 0,
+            new_cw3_address: Addr::unchecked(OWNER),
+            approvals: vec![Addr::unchecked(GUARDIANS[0]), Addr::unchecked(GUARDIANS[1])],
+            proposed_at: now,
+            executable_at: Some(now + RECOVERY_DELAY),
+        }]
+    );
+
+    let err = suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteRecovery { id: 0 })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::RecoveryDelayNotDone {
+            executable_at: now + RECOVERY_DELAY,
+        },
+    );
+
+    // anyone can push it through once the veto window is over
+    suite.advance_time(RECOVERY_DELAY);
+    suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteRecovery { id: 0 })
+        .unwrap();
+
+    let config: ConfigResponse = suite.query(&QueryMsg::Config {}).unwrap();
+    assert_eq!(config.cw3_address, Addr::unchecked(OWNER));
+
+    let res: RecoveryProposalsResponse = suite
+        .query(&QueryMsg::RecoveryProposals {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert!(res.proposals.is_empty());
+
+    // the recovered address manages the wallet from now on
+    suite
+        .execute_as(
+            OWNER,
+            &ExecuteMsg::ReplaceMultisig {
+                address: suite.multisig.to_string(),
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn recovery_veto() {
+    let mut suite = setup_guardians();
+
+    suite
+        .execute_as(
+            GUARDIANS[0],
+            &ExecuteMsg::ProposeRecovery {
+                new_cw3_address: String::from(STRANGER),
+            },
+        )
+        .unwrap();
+    suite
+        .execute_as(GUARDIANS[2], &ExecuteMsg::ApproveRecovery { id: 0 })
+        .unwrap();
+
+    let err = suite
+        .execute_as(GUARDIANS[0], &ExecuteMsg::VetoRecovery { id: 0 })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+
+    suite.multisig(&ExecuteMsg::VetoRecovery { id: 0 }).unwrap();
+
+    suite.advance_time(RECOVERY_DELAY);
+    let err = suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteRecovery { id: 0 })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::RecoveryProposalNotFound {});
+
+    let config: ConfigResponse = suite.query(&QueryMsg::Config {}).unwrap();
+    assert_eq!(config.cw3_address, suite.multisig);
+}
+
+#[test]
+fn update_guardians() {
+    let mut suite = setup_guardians();
+
+    suite
+        .execute_as(
+            GUARDIANS[0],
+            &ExecuteMsg::ProposeRecovery {
+                new_cw3_address: String::from(STRANGER),
+            },
+        )
+        .unwrap();
+
+    let err = suite
+        .multisig(&ExecuteMsg::UpdateGuardians {
+            guardians: vec![String::from(GUARDIANS[0])],
+            threshold: 2,
+            delay: RECOVERY_DELAY,
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::InvalidGuardians {
+            reason: String::from("threshold must be between 1 and the number of guardians"),
+        },
+    );
+
+    // a new guardian set drops pending proposals
+    suite
+        .multisig(&ExecuteMsg::UpdateGuardians {
+            guardians: vec![],
+            threshold: 0,
+            delay: 0,
+        })
+        .unwrap();
+    let res: RecoveryProposalsResponse = suite
+        .query(&QueryMsg::RecoveryProposals {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert!(res.proposals.is_empty());
+
+    let err = suite
+        .execute_as(
+            GUARDIANS[0],
+            &ExecuteMsg::ProposeRecovery {
+                new_cw3_address: String::from(STRANGER),
+            },
+        )
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn freeze_blocks_hot_actions() {
+    let mut suite = setup_guardians();
+
+    let err = suite
+        .execute_as(
+            STRANGER,
+            &ExecuteMsg::Freeze {
+                reason: String::from("lol"),
+            },
+        )
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+
+    let err = suite.multisig(&ExecuteMsg::Unfreeze {}).unwrap_err();
+    assert_contract_error(err, ContractError::NotFrozen {});
+
+    let now = suite.app.block_info().time.seconds();
+    suite
+        .hot(&ExecuteMsg::Freeze {
+            reason: String::from("key leak"),
+        })
+        .unwrap();
+
+    let res: FreezeResponse = suite.query(&QueryMsg::Freeze {}).unwrap();
+    assert_eq!(
+        res.freeze,
+        Some(FreezeInfo {
+            reason: String::from("key leak"),
+            initiator: Addr::unchecked(HOT),
+            frozen_at: now,
+        })
+    );
+
+    // the first freeze stays on record
+    let err = suite
+        .execute_as(
+            GUARDIANS[0],
+            &ExecuteMsg::Freeze {
+                reason: String::from("me too"),
+            },
+        )
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Frozen {});
+
+    let frozen_msgs = [
+        ExecuteMsg::AnchorEarnDeposit {
+            amount: Uint128::new(1u128),
+        },
+        ExecuteMsg::BlunaClaim {},
+        ExecuteMsg::RepayStable {
+            amount: Uint128::new(1u128),
+        },
+        ExecuteMsg::FillUpGas {},
+        ExecuteMsg::RunAction {
+            id: 1000,
+            params: vec![],
+        },
+    ];
+    for msg in frozen_msgs.iter() {
+        let err = suite.hot(msg).unwrap_err();
+        assert_contract_error(err, ContractError::Frozen {});
+    }
+
+    // hot wallets can't lift it
+    let err = suite.hot(&ExecuteMsg::Unfreeze {}).unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+
+    suite.multisig(&ExecuteMsg::Unfreeze {}).unwrap();
+    let res: FreezeResponse = suite.query(&QueryMsg::Freeze {}).unwrap();
+    assert_eq!(res.freeze, None);
+    suite.hot(&ExecuteMsg::BlunaClaim {}).unwrap();
+
+    // guardians can freeze as well
+    suite
+        .execute_as(
+            GUARDIANS[1],
+            &ExecuteMsg::Freeze {
+                reason: String::from("suspicious gas refills"),
+            },
+        )
+        .unwrap();
+    let err = suite.hot(&ExecuteMsg::FillUpGas {}).unwrap_err();
+    assert_contract_error(err, ContractError::Frozen {});
+}
+
+const TIMELOCK_DELAY: u64 = 3600u64;
+
+fn setup_timelock() -> Suite {
+    let mut suite = setup_guardians();
+    suite
+        .multisig(&ExecuteMsg::UpdateTimelock {
+            timelock: Some(TimelockConfig {
+                delay: TIMELOCK_DELAY,
+                thresholds: uusd(10_000_000u128),
+                kinds: vec![TimelockKind::ReplaceMultisig],
+            }),
+        })
+        .unwrap();
+    suite
+}
+
+fn send_to_owner(amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Execute {
+        command: CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from(OWNER),
+            amount: uusd(amount),
+        }),
+    }
+}
+
+fn queued_ids(suite: &Suite) -> Vec<u64> {
+    let res: QueuedCommandsResponse = suite
+        .query(&QueryMsg::QueuedCommands {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    res.commands.iter().map(|x| x.id).collect()
+}
+
+#[test]
+fn timelocked_execute() {
+    let mut suite = setup_timelock();
+
+    let err = suite
+        .multisig(&ExecuteMsg::UpdateTimelock {
+            timelock: Some(TimelockConfig {
+                delay: 0,
+                thresholds: vec![],
+                kinds: vec![],
+            }),
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::InvalidTimelock {
+            reason: String::from("delay must be non-zero"),
+        },
+    );
+
+    // below the threshold goes straight through
+    suite.multisig(&send_to_owner(1_000_000u128)).unwrap();
+    assert_eq!(suite.balance(&Addr::unchecked(OWNER)), 1_000_000u128);
+
+    let now = suite.app.block_info().time.seconds();
+    suite.multisig(&send_to_owner(20_000_000u128)).unwrap();
+    assert_eq!(suite.balance(&Addr::unchecked(OWNER)), 1_000_000u128);
+
+    let res: QueuedCommandsResponse = suite
+        .query(&QueryMsg::QueuedCommands {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(res.commands.len(), 1);
+    assert_eq!(res.commands[0].eta, now + TIMELOCK_DELAY);
+    assert_eq!(
+        res.commands[0].command,
+        TimelockedCommand::Execute {
+            command: CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from(OWNER),
+                amount: uusd(20_000_000u128),
+            }),
+        }
+    );
+
+    let err = suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteQueued { id: 0 })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::TimelockNotDone {
+            eta: now + TIMELOCK_DELAY,
+        },
+    );
+
+    // anyone can run it once the eta has passed, unless the wallet is frozen
+    suite.advance_time(TIMELOCK_DELAY);
+    suite
+        .hot(&ExecuteMsg::Freeze {
+            reason: String::from("check the queue"),
+        })
+        .unwrap();
+    let err = suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteQueued { id: 0 })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Frozen {});
+    suite.multisig(&ExecuteMsg::Unfreeze {}).unwrap();
+
+    let res = suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteQueued { id: 0 })
+        .unwrap();
+    assert_eq!(suite.balance(&Addr::unchecked(OWNER)), 21_000_000u128);
+    assert!(res.events.iter().any(|event| {
+        event
+            .attributes
+            .iter()
+            .any(|x| x.key == "target" && x.value == OWNER)
+            && event
+                .attributes
+                .iter()
+                .any(|x| x.key == "amount" && x.value == "20000000uusd")
+    }));
+    assert!(queued_ids(&suite).is_empty());
+
+    let err = suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteQueued { id: 0 })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::QueuedCommandNotFound {});
+}
+
+#[test]
+fn timelocked_cw20_withdraw() {
+    let mut suite = setup();
+
+    let cw20_code_id = suite.app.store_code(contract_cw20());
+    let token = suite
+        .app
+        .instantiate_contract(
+            cw20_code_id,
+            Addr::unchecked(OWNER),
+            &cw20_base::msg::InstantiateMsg {
+                name: String::from("mirror"),
+                symbol: String::from("MIR"),
+                decimals: 6,
+                initial_balances: vec![Cw20Coin {
+                    address: suite.wallet.to_string(),
+                    amount: Uint128::new(1_000_000u128),
+                }],
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            "mir",
+            None,
+        )
+        .unwrap();
+
+    // cw20 thresholds are keyed by the token address
+    suite
+        .multisig(&ExecuteMsg::UpdateTimelock {
+            timelock: Some(TimelockConfig {
+                delay: TIMELOCK_DELAY,
+                thresholds: vec![Coin::new(500_000u128, token.as_str())],
+                kinds: vec![],
+            }),
+        })
+        .unwrap();
+
+    let cw20_balance = |suite: &Suite, address: &str| -> u128 {
+        let res: Cw20BalanceResponse = suite
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &token,
+                &Cw20QueryMsg::Balance {
+                    address: String::from(address),
+                },
+            )
+            .unwrap();
+        res.balance.u128()
+    };
+
+    suite
+        .multisig(&ExecuteMsg::WithdrawCw20 {
+            token: token.to_string(),
+            amount: Uint128::new(100_000u128),
+            recipient: String::from(OWNER),
+        })
+        .unwrap();
+    assert_eq!(cw20_balance(&suite, OWNER), 100_000u128);
+    assert!(queued_ids(&suite).is_empty());
+
+    suite
+        .multisig(&ExecuteMsg::WithdrawCw20 {
+            token: token.to_string(),
+            amount: Uint128::new(600_000u128),
+            recipient: String::from(OWNER),
+        })
+        .unwrap();
+    assert_eq!(cw20_balance(&suite, OWNER), 100_000u128);
+    assert_eq!(queued_ids(&suite), vec![0]);
+
+    // a hand-crafted send is caught the same way
+    suite
+        .multisig(&ExecuteMsg::Execute {
+            command: CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: token.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: suite.market.to_string(),
+                    amount: Uint128::new(500_000u128),
+                    msg: Binary::default(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        })
+        .unwrap();
+    assert_eq!(queued_ids(&suite), vec![0, 1]);
+
+    suite.advance_time(TIMELOCK_DELAY);
+    suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteQueued { id: 0 })
+        .unwrap();
+    assert_eq!(cw20_balance(&suite, OWNER), 700_000u128);
+}
+
+#[test]
+fn timelocked_replace_multisig_cancelled() {
+    let mut suite = setup_timelock();
+
+    suite
+        .multisig(&ExecuteMsg::ReplaceMultisig {
+            address: String::from(STRANGER),
+        })
+        .unwrap();
+    assert_eq!(queued_ids(&suite), vec![0]);
+
+    let err = suite
+        .execute_as(STRANGER, &ExecuteMsg::CancelQueued { id: 0 })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+
+    suite
+        .execute_as(GUARDIANS[2], &ExecuteMsg::CancelQueued { id: 0 })
+        .unwrap();
+
+    suite.advance_time(TIMELOCK_DELAY);
+    let err = suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteQueued { id: 0 })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::QueuedCommandNotFound {});
+
+    let config: ConfigResponse = suite.query(&QueryMsg::Config {}).unwrap();
+    assert_eq!(config.cw3_address, suite.multisig);
+
+    // the multisig can cancel its own commands too
+    suite.multisig(&send_to_owner(50_000_000u128)).unwrap();
+    suite.multisig(&ExecuteMsg::CancelQueued { id: 1 }).unwrap();
+    assert!(queued_ids(&suite).is_empty());
+}
+
+#[test]
+fn timelock_update_is_timelocked() {
+    let mut suite = setup_timelock();
+
+    suite
+        .multisig(&ExecuteMsg::UpdateTimelock { timelock: None })
+        .unwrap();
+    let res: TimelockResponse = suite.query(&QueryMsg::Timelock {}).unwrap();
+    assert!(res.timelock.is_some());
+
+    suite.advance_time(TIMELOCK_DELAY);
+    suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteQueued { id: 0 })
+        .unwrap();
+    let res: TimelockResponse = suite.query(&QueryMsg::Timelock {}).unwrap();
+    assert_eq!(res.timelock, None);
+
+    suite.multisig(&send_to_owner(50_000_000u128)).unwrap();
+    assert_eq!(suite.balance(&Addr::unchecked(OWNER)), 50_000_000u128);
+}
+
+#[test]
+fn timelocked_config_updates() {
+    let mut suite = setup_timelock();
+    let hot_wallet = suite.hot_wallet(vec![0, 13]);
+
+    // anyone else is turned away rather than queued
+    let err = suite
+        .execute_as(
+            STRANGER,
+            &ExecuteMsg::UpsertHot {
+                hot_wallet: hot_wallet.clone(),
+            },
+        )
+        .unwrap_err();
+    assert_contract_error(err, ContractError::Unauthorized {});
+    assert!(queued_ids(&suite).is_empty());
+
+    suite
+        .multisig(&ExecuteMsg::UpsertHot {
+            hot_wallet: hot_wallet.clone(),
+        })
+        .unwrap();
+    assert_eq!(queued_ids(&suite), vec![0]);
+    let res: HotWallet = suite
+        .query(&QueryMsg::HotWalletConfig {
+            address: String::from(HOT),
+        })
+        .unwrap();
+    assert_ne!(res, hot_wallet);
+
+    suite.advance_time(TIMELOCK_DELAY);
+    suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteQueued { id: 0 })
+        .unwrap();
+    let res: HotWallet = suite
+        .query(&QueryMsg::HotWalletConfig {
+            address: String::from(HOT),
+        })
+        .unwrap();
+    assert_eq!(res, hot_wallet);
+
+    // removals narrow what hot wallets can do, so they apply right away
+    suite
+        .multisig(&ExecuteMsg::RemoveHot {
+            address: String::from(HOT),
+        })
+        .unwrap();
+    assert!(queued_ids(&suite).is_empty());
+}
+
+#[test]
+fn replacing_the_multisig_drops_queued_commands() {
+    let mut suite = setup_timelock();
+    suite.multisig(&send_to_owner(20_000_000u128)).unwrap();
+    assert_eq!(queued_ids(&suite), vec![0]);
+
+    // guardians recover the multisig
+    suite
+        .execute_as(
+            GUARDIANS[0],
+            &ExecuteMsg::ProposeRecovery {
+                new_cw3_address: String::from(OWNER),
+            },
+        )
+        .unwrap();
+    suite
+        .execute_as(GUARDIANS[1], &ExecuteMsg::ApproveRecovery { id: 0 })
+        .unwrap();
+    suite.advance_time(RECOVERY_DELAY);
+    suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteRecovery { id: 0 })
+        .unwrap();
+    assert!(queued_ids(&suite).is_empty());
+
+    let err = suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteQueued { id: 0 })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::QueuedCommandNotFound {});
+    assert_eq!(suite.balance(&Addr::unchecked(OWNER)), 0u128);
+
+    // a replacement through the timelock drops whatever else was queued
+    suite
+        .execute_as(OWNER, &send_to_owner(20_000_000u128))
+        .unwrap();
+    suite
+        .execute_as(
+            OWNER,
+            &ExecuteMsg::ReplaceMultisig {
+                address: suite.multisig.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(queued_ids(&suite), vec![1, 2]);
+
+    suite.advance_time(TIMELOCK_DELAY);
+    suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteQueued { id: 2 })
+        .unwrap();
+    assert!(queued_ids(&suite).is_empty());
+    let config: ConfigResponse = suite.query(&QueryMsg::Config {}).unwrap();
+    assert_eq!(config.cw3_address, suite.multisig);
+}
+
+const BENEFICIARY_DELAY: u64 = 86400u64;
+
+fn send_to(address: &str, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Execute {
+        command: CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from(address),
+            amount: uusd(amount),
+        }),
+    }
+}
+
+#[test]
+fn beneficiary_allowlist() {
+    let mut suite = setup();
+
+    let err = suite
+        .multisig(&ExecuteMsg::UpdateBeneficiaryPolicy {
+            policy: Some(BeneficiaryPolicy { delay: 0 }),
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::InvalidBeneficiaryPolicy {
+            reason: String::from("delay must be non-zero"),
+        },
+    );
+
+    // enabling applies right away
+    suite
+        .multisig(&ExecuteMsg::UpdateBeneficiaryPolicy {
+            policy: Some(BeneficiaryPolicy {
+                delay: BENEFICIARY_DELAY,
+            }),
+        })
+        .unwrap();
+
+    let err = suite.multisig(&send_to(OWNER, 1_000_000u128)).unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::BeneficiaryNotAllowed {
+            address: String::from(OWNER),
+        },
+    );
+
+    // cw20 transfers are inspected too
+    let err = suite
+        .multisig(&ExecuteMsg::WithdrawCw20 {
+            token: String::from("token"),
+            amount: Uint128::new(1_000u128),
+            recipient: String::from(STRANGER),
+        })
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::BeneficiaryNotAllowed {
+            address: String::from(STRANGER),
+        },
+    );
+
+    // coins attached to contract calls count the called contract as a recipient
+    let deposit_to = |contract_addr: String| ExecuteMsg::Execute {
+        command: CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg: to_binary(&MarketExecuteMsg::DepositStable {}).unwrap(),
+            funds: uusd(1_000_000u128),
+        }),
+    };
+    let err = suite
+        .multisig(&deposit_to(String::from(STRANGER)))
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::BeneficiaryNotAllowed {
+            address: String::from(STRANGER),
+        },
+    );
+
+    // contracts whitelisted before the policy count as beneficiaries
+    suite
+        .multisig(&deposit_to(suite.market.to_string()))
+        .unwrap();
+
+    let err = suite
+        .multisig(&ExecuteMsg::Execute {
+            command: CosmosMsg::Wasm(WasmMsg::Instantiate {
+                admin: None,
+                code_id: suite.market_code_id,
+                msg: to_binary(&Empty {}).unwrap(),
+                funds: uusd(1_000_000u128),
+                label: String::from("market"),
+            }),
+        })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::FundedInstantiate {});
+
+    // gas refills too, so a freshly upserted hot wallet can't drain the tank
+    let err = suite.hot(&ExecuteMsg::FillUpGas {}).unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::BeneficiaryNotAllowed {
+            address: String::from(HOT),
+        },
+    );
+
+    // a new beneficiary waits out the delay
+    let now = suite.app.block_info().time.seconds();
+    suite
+        .multisig(&ExecuteMsg::AddBeneficiary {
+            address: String::from(OWNER),
+        })
+        .unwrap();
+    let res: BeneficiariesResponse = suite
+        .query(&QueryMsg::Beneficiaries {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(
+        res.beneficiaries,
+        vec![Beneficiary {
+            address: String::from(OWNER),
+            active_at: now + BENEFICIARY_DELAY,
+        }]
+    );
+
+    let err = suite.multisig(&send_to(OWNER, 1_000_000u128)).unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::BeneficiaryNotAllowed {
+            address: String::from(OWNER),
+        },
+    );
+
+    suite.advance_time(BENEFICIARY_DELAY);
+    suite.multisig(&send_to(OWNER, 1_000_000u128)).unwrap();
+    assert_eq!(suite.balance(&Addr::unchecked(OWNER)), 1_000_000u128);
+
+    // disabling is pending for the current delay as well
+    let now = suite.app.block_info().time.seconds();
+    suite
+        .multisig(&ExecuteMsg::UpdateBeneficiaryPolicy { policy: None })
+        .unwrap();
+    let res: BeneficiaryPolicyResponse = suite.query(&QueryMsg::BeneficiaryPolicy {}).unwrap();
+    assert_eq!(
+        res,
+        BeneficiaryPolicyResponse {
+            policy: Some(BeneficiaryPolicy {
+                delay: BENEFICIARY_DELAY,
+            }),
+            pending: Some(PendingBeneficiaryPolicy {
+                policy: None,
+                effective_at: now + BENEFICIARY_DELAY,
+            }),
+        }
+    );
+
+    let err = suite
+        .multisig(&send_to(STRANGER, 1_000_000u128))
+        .unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::BeneficiaryNotAllowed {
+            address: String::from(STRANGER),
+        },
+    );
+
+    suite.advance_time(BENEFICIARY_DELAY);
+    suite.multisig(&send_to(STRANGER, 1_000_000u128)).unwrap();
+    assert_eq!(suite.balance(&Addr::unchecked(STRANGER)), 1_000_000u128);
+
+    suite
+        .multisig(&ExecuteMsg::RemoveBeneficiary {
+            address: String::from(OWNER),
+        })
+        .unwrap();
+    let err = suite
+        .multisig(&ExecuteMsg::RemoveBeneficiary {
+            address: String::from(OWNER),
+        })
+        .unwrap_err();
+    assert_contract_error(err, ContractError::BeneficiaryNotFound {});
+}
+
+#[test]
+fn action_history() {
+    let mut suite = setup();
+
+    suite
+        .hot(&ExecuteMsg::RepayStable {
+            amount: Uint128::new(10_000_000u128),
+        })
+        .unwrap();
+    suite
+        .multisig(&ExecuteMsg::UpdateBondValidators {
+            validators: vec![String::from(
+                "terravaloper1krj7amhhagjnyg2tkkuh6l0550y733jnjnnlzy",
+            )],
+        })
+        .unwrap();
+
+    // strangers don't get journaled
+    suite
+        .execute_as(STRANGER, &ExecuteMsg::FillUpGas {})
+        .unwrap_err();
+    suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteQueued { id: 0 })
+        .unwrap_err();
+
+    let res: ActionHistoryResponse = suite
+        .query(&QueryMsg::ActionHistory {
+            hot_wallet: Some(String::from(HOT)),
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(res.entries.len(), 1);
+    let entry = &res.entries[0];
+    assert_eq!(entry.actor, HOT);
+    assert_eq!(entry.role, ActorRole::HotWallet);
+    assert_eq!(entry.action, "repay_stable");
+    assert_eq!(
+        entry
+            .attributes
+            .iter()
+            .map(|x| (x.key.as_str(), x.value.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("action", "repay_stable"),
+            ("action_id", "2"),
+            ("contract", suite.market.as_str()),
+            ("denom", "uusd"),
+            ("amount", "10000000"),
+            // 0.1% tax on top of the repay
+            ("tax", "10000"),
+            (
+                "balance",
+                (WALLET_FUNDS - 10_000_000u128).to_string().as_str()
+            ),
+            ("actor", HOT),
+        ]
+    );
+
+    let res: ActionHistoryResponse = suite
+        .query(&QueryMsg::ActionHistory {
+            hot_wallet: None,
+            start_after: Some(entry.id),
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(res.entries.len(), 1);
+    assert_eq!(res.entries[0].id, entry.id + 1);
+    assert_eq!(res.entries[0].actor, suite.multisig.as_str());
+    assert_eq!(res.entries[0].role, ActorRole::Multisig);
+    assert_eq!(res.entries[0].action, "update_bond_validators");
+}
+
+#[test]
+fn action_history_replies_and_recovery() {
+    let mut suite = setup_guardians();
+    let hot_wallet = suite.hot_wallet(vec![11]);
+    suite
+        .multisig(&ExecuteMsg::UpsertHot { hot_wallet })
+        .unwrap();
+
+    // the compound's deposit reply is journaled under the hot wallet
+    suite.hot(&ExecuteMsg::BlunaCompound {}).unwrap();
+    let res: ActionHistoryResponse = suite
+        .query(&QueryMsg::ActionHistory {
+            hot_wallet: Some(String::from(HOT)),
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(
+        res.entries
+            .iter()
+            .map(|x| (x.role.clone(), x.action.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (ActorRole::HotWallet, "bluna_compound"),
+            (ActorRole::HotWallet, "bluna_compound_deposit"),
+        ]
+    );
+
+    suite
+        .execute_as(
+            GUARDIANS[0],
+            &ExecuteMsg::ProposeRecovery {
+                new_cw3_address: String::from(OWNER),
+            },
+        )
+        .unwrap();
+    suite
+        .execute_as(GUARDIANS[1], &ExecuteMsg::ApproveRecovery { id: 0 })
+        .unwrap();
+    suite.advance_time(RECOVERY_DELAY);
+    suite
+        .execute_as(STRANGER, &ExecuteMsg::ExecuteRecovery { id: 0 })
+        .unwrap();
+
+    let res: ActionHistoryResponse = suite
+        .query(&QueryMsg::ActionHistory {
+            hot_wallet: None,
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(
+        res.entries
+            .iter()
+            .map(|x| (x.actor.as_str(), x.role.clone(), x.action.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                suite.multisig.as_str(),
+                ActorRole::Multisig,
+                "update_guardians"
+            ),
+            (suite.multisig.as_str(), ActorRole::Multisig, "upsert_hot"),
+            (HOT, ActorRole::HotWallet, "bluna_compound"),
+            (HOT, ActorRole::HotWallet, "bluna_compound_deposit"),
+            (GUARDIANS[0], ActorRole::Guardian, "propose_recovery"),
+            (GUARDIANS[1], ActorRole::Guardian, "approve_recovery"),
+            (STRANGER, ActorRole::Anyone, "execute_recovery"),
+        ]
+    );
+}
+
+#[test]
+fn beneficiary_policy_covers_hot_actions() {
+    let mut suite = setup();
+    suite
+        .multisig(&ExecuteMsg::UpdateBeneficiaryPolicy {
+            policy: Some(BeneficiaryPolicy {
+                delay: BENEFICIARY_DELAY,
+            }),
+        })
+        .unwrap();
+
+    let deposit = ExecuteMsg::AnchorEarnDeposit {
+        amount: Uint128::new(1_000_000u128),
+    };
+    suite.hot(&deposit).unwrap();
+
+    // a contract whitelisted under the policy only receives funds once its delay has passed
+    let market2 = suite
+        .app
+        .instantiate_contract(
+            suite.market_code_id,
+            Addr::unchecked(OWNER),
+            &Empty {},
+            &[],
+            "market2",
+            None,
+        )
+        .unwrap();
+    let now = suite.app.block_info().time.seconds();
+    let res = suite
+        .multisig(&ExecuteMsg::UpdateWhitelistedContract {
+            contract: WhitelistedContract {
+                address: market2.to_string(),
+                label: String::from("anchor_market"),
+                code_id: suite.market_code_id,
+            },
+        })
+        .unwrap();
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(|x| x.key == "active_at" && x.value == (now + BENEFICIARY_DELAY).to_string())));
+
+    let err = suite.hot(&deposit).unwrap_err();
+    assert_contract_error(
+        err,
+        ContractError::BeneficiaryNotAllowed {
+            address: market2.to_string(),
+        },
+    );
+
+    suite.advance_time(BENEFICIARY_DELAY);
+    suite.hot(&deposit).unwrap();
+    assert_eq!(suite.balance(&market2), 1_000_000u128);
+}
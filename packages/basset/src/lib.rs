@@ -1,13 +1,13 @@
 mod tax_querier;
 
-pub use tax_querier::deduct_tax;
+pub use tax_querier::{compute_tax, deduct_tax};
 pub mod airdrop;
 pub mod contract_error;
 pub mod hub;
 pub mod reward;
 
-#[cfg(test)]
-mod mock_querier;
+#[cfg(any(test, feature = "mock_querier"))]
+pub mod mock_querier;
 
 #[cfg(test)]
 mod testing;
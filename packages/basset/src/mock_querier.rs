@@ -1,19 +1,37 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
 use cosmwasm_std::{
-    from_slice, to_binary, AllBalanceResponse, Api, BalanceResponse, BankQuery, CanonicalAddr,
-    Coin, ContractResult, Decimal, OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError,
-    SystemResult, Uint128, WasmQuery,
+    from_binary, from_slice, to_binary, AllBalanceResponse, Api, BalanceResponse, BankQuery,
+    Binary, CanonicalAddr, Coin, ContractResult, Decimal, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use cosmwasm_storage::to_length_prefixed;
-use std::collections::HashMap;
-
-use crate::hub::Config;
+use moneymarket::market::{
+    BorrowerInfoResponse, EpochStateResponse, QueryMsg as MarketQueryMsg,
+    StateResponse as MarketStateResponse,
+};
+use moneymarket::oracle::{PriceResponse, QueryMsg as OracleQueryMsg};
+use moneymarket::overseer::{CollateralsResponse, QueryMsg as OverseerQueryMsg};
+use moneymarket::tokens::TokensHuman;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
+use std::collections::HashMap;
+
+use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg};
+
+use crate::hub::{
+    Config, QueryMsg as HubQueryMsg, UnbondRequest, UnbondRequestsResponse, WithdrawableUnbondedResponse,
+};
+use crate::reward::{AccruedRewardsResponse, QueryMsg as RewardQueryMsg};
+use terra_cosmwasm::{
+    ContractInfoResponse, TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper,
+    TerraRoute,
+};
 
 pub const MOCK_CONTRACT_ADDR: &str = "cosmos2contract";
 
+/// mock deps whose querier answers terra treasury and contract info, cw20 balance, anchor and bluna reward queries.
+/// use `deps.querier.with_*` to configure the responses
 pub fn mock_dependencies(
     contract_balance: &[Coin],
 ) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
@@ -53,9 +71,59 @@ pub(crate) fn caps_to_map(caps: &[(&String, &Uint128)]) -> HashMap<String, Uint1
     owner_map
 }
 
+/// code id each contract address runs, for terra wasm ContractInfo
+#[derive(Clone, Default)]
+pub struct ContractInfoQuerier {
+    code_ids: HashMap<String, u64>,
+}
+
+/// State/EpochState responses and borrower loans per anchor market address
+#[derive(Clone, Default)]
+pub struct MarketQuerier {
+    states: HashMap<String, (MarketStateResponse, EpochStateResponse)>,
+    loans: HashMap<String, HashMap<String, Uint256>>,
+}
+
+/// locked collaterals per borrower, per anchor overseer address
+#[derive(Clone, Default)]
+pub struct OverseerQuerier {
+    collaterals: HashMap<String, HashMap<String, TokensHuman>>,
+}
+
+/// uusd prices per asset, per anchor oracle address
+#[derive(Clone, Default)]
+pub struct OracleQuerier {
+    prices: HashMap<String, HashMap<String, Decimal256>>,
+}
+
+/// cw20 balances per holder, per token contract address
+#[derive(Clone, Default)]
+pub struct TokenQuerier {
+    balances: HashMap<String, HashMap<String, Uint128>>,
+}
+
+/// withdrawable uluna and pending unbond requests per holder, per bluna hub address
+#[derive(Clone, Default)]
+pub struct HubQuerier {
+    unbonds: HashMap<String, HashMap<String, (Uint128, UnbondRequest)>>,
+}
+
+/// accrued rewards per holder, per bluna reward contract address
+#[derive(Clone, Default)]
+pub struct RewardQuerier {
+    rewards: HashMap<String, HashMap<String, Uint128>>,
+}
+
 pub struct WasmMockQuerier {
     base: MockQuerier<TerraQueryWrapper>,
     tax_querier: TaxQuerier,
+    contract_info_querier: ContractInfoQuerier,
+    market_querier: MarketQuerier,
+    reward_querier: RewardQuerier,
+    overseer_querier: OverseerQuerier,
+    oracle_querier: OracleQuerier,
+    token_querier: TokenQuerier,
+    hub_querier: HubQuerier,
 }
 
 impl Querier for WasmMockQuerier {
@@ -98,10 +166,51 @@ impl WasmMockQuerier {
                         }
                         _ => panic!("DO NOT ENTER HERE"),
                     }
+                } else if &TerraRoute::Wasm == route {
+                    match query_data {
+                        TerraQuery::ContractInfo { contract_address } => {
+                            match self.contract_info_querier.code_ids.get(contract_address) {
+                                Some(code_id) => {
+                                    let res = ContractInfoResponse {
+                                        address: contract_address.clone(),
+                                        creator: String::from("creator"),
+                                        code_id: *code_id,
+                                        admin: None,
+                                    };
+                                    SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                                }
+                                None => SystemResult::Err(SystemError::NoSuchContract {
+                                    addr: contract_address.clone(),
+                                }),
+                            }
+                        }
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
                 } else {
                     panic!("DO NOT ENTER HERE")
                 }
             }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                if self.market_querier.states.contains_key(contract_addr)
+                    || self.market_querier.loans.contains_key(contract_addr)
+                {
+                    self.handle_market_query(contract_addr, msg)
+                } else if let Some(rewards) = self.reward_querier.rewards.get(contract_addr) {
+                    self.handle_reward_query(rewards, msg)
+                } else if let Some(collaterals) =
+                    self.overseer_querier.collaterals.get(contract_addr)
+                {
+                    self.handle_overseer_query(collaterals, msg)
+                } else if let Some(prices) = self.oracle_querier.prices.get(contract_addr) {
+                    self.handle_oracle_query(prices, msg)
+                } else if let Some(balances) = self.token_querier.balances.get(contract_addr) {
+                    self.handle_token_query(balances, msg)
+                } else if let Some(unbonds) = self.hub_querier.unbonds.get(contract_addr) {
+                    self.handle_hub_query(unbonds, msg)
+                } else {
+                    self.base.handle_query(request)
+                }
+            }
             QueryRequest::Wasm(WasmQuery::Raw {
                 contract_addr: _,
                 key,
@@ -124,7 +233,7 @@ impl WasmMockQuerier {
                     };
                     SystemResult::Ok(ContractResult::from(to_binary(&config)))
                 } else {
-                    unimplemented!()
+                    self.base.handle_query(request)
                 }
             }
             QueryRequest::Bank(BankQuery::AllBalances { address }) => {
@@ -143,7 +252,7 @@ impl WasmMockQuerier {
                     let all_balances = AllBalanceResponse { amount: coins };
                     SystemResult::Ok(ContractResult::from(to_binary(&all_balances)))
                 } else {
-                    unimplemented!()
+                    self.base.handle_query(request)
                 }
             }
             QueryRequest::Bank(BankQuery::Balance { address, denom }) => {
@@ -156,17 +265,139 @@ impl WasmMockQuerier {
                     };
                     SystemResult::Ok(ContractResult::from(to_binary(&bank_res)))
                 } else {
-                    unimplemented!()
+                    self.base.handle_query(request)
                 }
             }
             _ => self.base.handle_query(request),
         }
     }
+
+    fn handle_market_query(&self, market_addr: &str, msg: &Binary) -> QuerierResult {
+        let state = self.market_querier.states.get(market_addr);
+        match (from_binary(msg), state) {
+            (Ok(MarketQueryMsg::State { .. }), Some(state)) => {
+                SystemResult::Ok(ContractResult::from(to_binary(&state.0)))
+            }
+            (Ok(MarketQueryMsg::EpochState { .. }), Some(state)) => {
+                SystemResult::Ok(ContractResult::from(to_binary(&state.1)))
+            }
+            (Ok(MarketQueryMsg::BorrowerInfo { borrower, .. }), _) => {
+                let res = BorrowerInfoResponse {
+                    loan_amount: self
+                        .market_querier
+                        .loans
+                        .get(market_addr)
+                        .and_then(|x| x.get(&borrower))
+                        .copied()
+                        .unwrap_or_default(),
+                    borrower,
+                    interest_index: Decimal256::one(),
+                    reward_index: Decimal256::zero(),
+                    pending_rewards: Decimal256::zero(),
+                };
+                SystemResult::Ok(ContractResult::from(to_binary(&res)))
+            }
+            _ => panic!("DO NOT ENTER HERE"),
+        }
+    }
+
+    fn handle_reward_query(
+        &self,
+        rewards: &HashMap<String, Uint128>,
+        msg: &Binary,
+    ) -> QuerierResult {
+        match from_binary(msg) {
+            Ok(RewardQueryMsg::AccruedRewards { address }) => {
+                let res = AccruedRewardsResponse {
+                    rewards: rewards.get(&address).copied().unwrap_or_default(),
+                };
+                SystemResult::Ok(ContractResult::from(to_binary(&res)))
+            }
+            _ => panic!("DO NOT ENTER HERE"),
+        }
+    }
 }
 
-#[derive(Clone, Default)]
-pub struct TokenQuerier {
-    balances: HashMap<String, HashMap<String, Uint128>>,
+impl WasmMockQuerier {
+    fn handle_overseer_query(
+        &self,
+        collaterals: &HashMap<String, TokensHuman>,
+        msg: &Binary,
+    ) -> QuerierResult {
+        match from_binary(msg) {
+            Ok(OverseerQueryMsg::Collaterals { borrower }) => {
+                let res = CollateralsResponse {
+                    collaterals: collaterals.get(&borrower).cloned().unwrap_or_default(),
+                    borrower,
+                };
+                SystemResult::Ok(ContractResult::from(to_binary(&res)))
+            }
+            _ => panic!("DO NOT ENTER HERE"),
+        }
+    }
+
+    fn handle_oracle_query(
+        &self,
+        prices: &HashMap<String, Decimal256>,
+        msg: &Binary,
+    ) -> QuerierResult {
+        match from_binary(msg) {
+            Ok(OracleQueryMsg::Price { base, .. }) => match prices.get(&base) {
+                Some(rate) => {
+                    let res = PriceResponse {
+                        rate: *rate,
+                        last_updated_base: 0u64,
+                        last_updated_quote: 0u64,
+                    };
+                    SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                }
+                None => SystemResult::Ok(ContractResult::Err(format!("no price for {}", base))),
+            },
+            _ => panic!("DO NOT ENTER HERE"),
+        }
+    }
+
+    fn handle_hub_query(
+        &self,
+        unbonds: &HashMap<String, (Uint128, UnbondRequest)>,
+        msg: &Binary,
+    ) -> QuerierResult {
+        match from_binary(msg) {
+            Ok(HubQueryMsg::WithdrawableUnbonded { address }) => {
+                let res = WithdrawableUnbondedResponse {
+                    withdrawable: unbonds.get(&address).map(|x| x.0).unwrap_or_default(),
+                };
+                SystemResult::Ok(ContractResult::from(to_binary(&res)))
+            }
+            Ok(HubQueryMsg::UnbondRequests { address }) => {
+                let res = UnbondRequestsResponse {
+                    requests: unbonds
+                        .get(&address)
+                        .map(|x| x.1.clone())
+                        .unwrap_or_default(),
+                    address,
+                };
+                SystemResult::Ok(ContractResult::from(to_binary(&res)))
+            }
+            _ => panic!("DO NOT ENTER HERE"),
+        }
+    }
+
+    fn handle_token_query(
+        &self,
+        balances: &HashMap<String, Uint128>,
+        msg: &Binary,
+    ) -> QuerierResult {
+        match from_binary(msg) {
+            Ok(Cw20QueryMsg::Balance { address }) => {
+                let res = Cw20BalanceResponse {
+                    balance: balances.get(&address).copied().unwrap_or_default(),
+                };
+                SystemResult::Ok(ContractResult::from(to_binary(&res)))
+            }
+            _ => panic!("DO NOT ENTER HERE"),
+        }
+    }
 }
 
 impl WasmMockQuerier {
@@ -174,13 +405,107 @@ impl WasmMockQuerier {
         WasmMockQuerier {
             base,
             tax_querier: TaxQuerier::default(),
+            contract_info_querier: ContractInfoQuerier::default(),
+            market_querier: MarketQuerier::default(),
+            reward_querier: RewardQuerier::default(),
+            overseer_querier: OverseerQuerier::default(),
+            oracle_querier: OracleQuerier::default(),
+            token_querier: TokenQuerier::default(),
+            hub_querier: HubQuerier::default(),
+        }
+    }
+
+    // configure the bank balances, replacing whatever the address held before
+    pub fn with_balances(&mut self, balances: &[(&String, &[Coin])]) {
+        for (addr, balance) in balances.iter() {
+            self.base.update_balance(addr.to_string(), balance.to_vec());
         }
     }
 
+    // configure the cw20 balances held at the token contract at token_addr
+    pub fn with_token_balances(&mut self, token_addr: &str, balances: &[(&String, &Uint128)]) {
+        self.token_querier
+            .balances
+            .insert(token_addr.to_string(), caps_to_map(balances));
+    }
+
+    // configure what the bluna hub at hub_addr reports as withdrawable and pending for holder
+    pub fn with_unbonds(
+        &mut self,
+        hub_addr: &str,
+        holder: &str,
+        withdrawable: Uint128,
+        requests: UnbondRequest,
+    ) {
+        self.hub_querier
+            .unbonds
+            .entry(hub_addr.to_string())
+            .or_default()
+            .insert(holder.to_string(), (withdrawable, requests));
+    }
+
+    // configure the code id the contract at contract_addr runs
+    pub fn with_contract_info(&mut self, contract_addr: &str, code_id: u64) {
+        self.contract_info_querier
+            .code_ids
+            .insert(contract_addr.to_string(), code_id);
+    }
+
     // configure the tax mock querier
     pub fn with_tax(&mut self, rate: Decimal, caps: &[(&String, &Uint128)]) {
         self.tax_querier = TaxQuerier::new(rate, caps);
     }
+
+    // configure the State/EpochState answered by the anchor market at market_addr
+    pub fn with_market_state(
+        &mut self,
+        market_addr: &str,
+        state: MarketStateResponse,
+        epoch_state: EpochStateResponse,
+    ) {
+        self.market_querier
+            .states
+            .insert(market_addr.to_string(), (state, epoch_state));
+    }
+
+    // configure the AccruedRewards answered by the bluna reward contract at reward_addr
+    pub fn with_accrued_rewards(&mut self, reward_addr: &str, rewards: &[(&String, &Uint128)]) {
+        self.reward_querier
+            .rewards
+            .insert(reward_addr.to_string(), caps_to_map(rewards));
+    }
+
+    // configure the loan the anchor market at market_addr reports for borrower
+    pub fn with_loan(&mut self, market_addr: &str, borrower: &str, loan_amount: Uint256) {
+        self.market_querier
+            .loans
+            .entry(market_addr.to_string())
+            .or_default()
+            .insert(borrower.to_string(), loan_amount);
+    }
+
+    // configure the locked collaterals the anchor overseer at overseer_addr reports for borrower
+    pub fn with_collaterals(
+        &mut self,
+        overseer_addr: &str,
+        borrower: &str,
+        collaterals: TokensHuman,
+    ) {
+        self.overseer_querier
+            .collaterals
+            .entry(overseer_addr.to_string())
+            .or_default()
+            .insert(borrower.to_string(), collaterals);
+    }
+
+    // configure the uusd price the anchor oracle at oracle_addr reports for asset
+    pub fn with_oracle_price(&mut self, oracle_addr: &str, asset: &str, price: Decimal256) {
+        self.oracle_querier
+            .prices
+            .entry(oracle_addr.to_string())
+            .or_default()
+            .insert(asset.to_string(), price);
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -1,6 +1,9 @@
 use crate::deduct_tax;
 use crate::mock_querier::mock_dependencies;
+use crate::reward::{AccruedRewardsResponse, QueryMsg as RewardQueryMsg};
+use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{Coin, Decimal, Uint128};
+use moneymarket::market::{EpochStateResponse, QueryMsg as MarketQueryMsg, StateResponse};
 
 #[test]
 fn test_deduct_tax() {
@@ -29,3 +32,57 @@ fn test_deduct_tax() {
         }
     );
 }
+
+#[test]
+fn test_mock_contract_queries() {
+    let mut deps = mock_dependencies(&[]);
+
+    let epoch_state = EpochStateResponse {
+        exchange_rate: Decimal256::percent(120),
+        aterra_supply: Uint256::from(1000000u64),
+    };
+    deps.querier.with_market_state(
+        "market",
+        StateResponse {
+            total_liabilities: Decimal256::zero(),
+            total_reserves: Decimal256::zero(),
+            last_interest_updated: 0u64,
+            last_reward_updated: 0u64,
+            global_interest_index: Decimal256::one(),
+            global_reward_index: Decimal256::zero(),
+            anc_emission_rate: Decimal256::zero(),
+            prev_aterra_supply: Uint256::zero(),
+            prev_exchange_rate: Decimal256::one(),
+        },
+        epoch_state.clone(),
+    );
+    deps.querier.with_accrued_rewards(
+        "reward",
+        &[(&"holder".to_string(), &Uint128::from(1234u128))],
+    );
+
+    let res: EpochStateResponse = deps
+        .as_ref()
+        .querier
+        .query_wasm_smart(
+            "market",
+            &MarketQueryMsg::EpochState {
+                block_height: None,
+                distributed_interest: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res, epoch_state);
+
+    let res: AccruedRewardsResponse = deps
+        .as_ref()
+        .querier
+        .query_wasm_smart(
+            "reward",
+            &RewardQueryMsg::AccruedRewards {
+                address: "holder".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.rewards, Uint128::from(1234u128));
+}
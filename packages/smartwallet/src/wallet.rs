@@ -1,8 +1,10 @@
-use cosmwasm_std::{Uint128, Addr, CosmosMsg, Empty};
+use cosmwasm_std::{Uint128, Addr, Attribute, Coin, CosmosMsg, Decimal, Empty};
+use cosmwasm_bignumber::Decimal256;
+use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-/// ideally later we can also fabricate the cw3 during init
+// ideally later we can also fabricate the cw3 during init
 /*
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -57,31 +59,132 @@ pub enum Duration{
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     
-    //hardwired hot msgs with internal u64 ids
+    //hardwired hot msgs with internal u64 ids. these work out amounts on chain (balances, tax,
+    //reserves, ltv) which a template can't express, so they stay next to the registry
     AnchorEarnDeposit {amount: Uint128}, // id=0
     BlunaClaim {}, //id=1
     RepayStable {amount: Uint128}, //id=2
+    BorrowStable {amount: Uint128}, //id=3; capped at the loan config's target ltv
+    DepositCollateral {amount: Uint128}, //id=4; bluna, deposited in custody and locked
+    WithdrawCollateral {amount: Uint128}, //id=5; bluna, unlocked and withdrawn from custody
+    RebalanceLoan {}, //id=6; repays down to the loan config's safe ltv, redeeming aust if uusd runs short
+    AnchorEarnRedeem {amount: Uint128}, //id=7; aust, the uusd comes back to the smart wallet
+    BlunaBond {amount: Uint128, validator: String}, //id=8; uluna, validator must be approved by the multisig
+    BlunaUnbond {amount: Uint128}, //id=9; bluna, sent to the hub
+    BlunaWithdrawUnbonded {}, //id=10; matured uluna comes back to the smart wallet
+    BlunaCompound {}, //id=11; claims bluna rewards and deposits what arrived into anchor earn
+    ClaimAirdrop {label: String, stage: u8, amount: Uint128, proof: Vec<String>, swap: bool, belief_price: Option<Decimal>}, //id=12; swap sells the claim through the airdrop's pair and needs belief_price
+    Swap {pair: String, offer_amount: Uint128, belief_price: Decimal, max_spread: Decimal, min_receive: Uint128}, //id=13; reverts if less than min_receive, or what belief_price and max_spread imply, arrives
     FillUpGas {}, // no id check
 
+    //registry driven hot msg; id refers to a registered action template (ids from 1000 up).
+    //new automations are added here without a wasm upload
+    RunAction {id: u64, params: Vec<ActionParam>},
+
     //hot wallet mgmt; consider making a vector later on with a label field
     RemoveHot {address: String},
     UpsertHot {hot_wallet: HotWallet},
 
-    //whitelisted contract mgmt
+    //whitelisted contract mgmt; addresses and code ids are checked against the chain
     ReplaceContractWhitelist { whitelisted_contracts: Vec<WhitelistedContract> },
+    AddWhitelistedContract {contract: WhitelistedContract}, //multisig only; label must be new. receives funds after the beneficiary delay
+    UpdateWhitelistedContract {contract: WhitelistedContract}, //multisig only; replaces the entry with the same label
+    RemoveWhitelistedContract {label: String}, //multisig only
+
+    //anchor borrow mgmt
+    UpdateLoanConfig {loan_config: LoanConfig},
+
+    //validators BlunaBond may delegate to; a non-empty list of distinct valoper addresses
+    UpdateBondValidators {validators: Vec<String>},
+
+    //airdrops ClaimAirdrop may claim
+    UpsertAirdrop {airdrop: Airdrop},
+    RemoveAirdrop {label: String},
+
+    //terraswap pairs Swap may trade on
+    UpsertSwapPair {swap_pair: SwapPair},
+    RemoveSwapPair {label: String},
+
+    //action template registry mgmt
+    AddActionTemplate {template: ActionTemplate},
+    UpdateActionTemplate {id: u64, template: ActionTemplate},
+    RemoveActionTemplate {id: u64},
 
     //update multsig
     ReplaceMultisig {address: String},
 
-    //generalized exec for multisig
+    //emergency stop of all hot msgs, FillUpGas included
+    Freeze {reason: String}, //any hot wallet or guardian
+    Unfreeze {}, //multisig only
+
+    //guardian recovery of the multisig, for when it can no longer reach quorum
+    UpdateGuardians {guardians: Vec<String>, threshold: u64, delay: u64}, //multisig only; empty guardians disables recovery
+    ProposeRecovery {new_cw3_address: String}, //guardian; counts as the first approval
+    ApproveRecovery {id: u64}, //guardian
+    VetoRecovery {id: u64}, //multisig only
+    ExecuteRecovery {id: u64}, //anyone, once approved and the delay has passed
+
+    //generalized exec for multisig; queued instead when the timelock applies
     Execute {command: CosmosMsg<Empty>},
+
+    //cw20 custody
+    Receive(Cw20ReceiveMsg), //any cw20 may be sent here; only tracked tokens show up in Cw20Balances
+    AddTrackedToken {token: String}, //multisig only; token must answer a cw20 balance query
+    RemoveTrackedToken {token: String}, //multisig only
+    WithdrawCw20 {token: String, amount: Uint128, recipient: String}, //multisig only; goes through the timelock like Execute
+
+    //timelock on multisig commands
+    UpdateTimelock {timelock: Option<TimelockConfig>}, //multisig only; queued itself while a timelock is active
+    CancelQueued {id: u64}, //multisig or guardian
+    ExecuteQueued {id: u64}, //anyone, once the eta has passed
+
+    //beneficiary allowlist for outgoing transfers
+    UpdateBeneficiaryPolicy {policy: Option<BeneficiaryPolicy>}, //multisig only; disabling or shortening the delay waits out the current delay
+    AddBeneficiary {address: String}, //multisig only; allowed once the policy's delay has passed
+    RemoveBeneficiary {address: String}, //multisig only
+
+    //minimum balances hot msgs must leave in the smart wallet; cw20s keyed by token address
+    UpdateReserves {reserves: Vec<Coin>}, //multisig only; replaces every reserve
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
-    HotWallet {address: String},
+    GasTankStatus {address: String},
+    HotWalletConfig {address: String},
+    HotWallets {start_after: Option<String>, limit: Option<u32>},
+    HotWalletsByLabel {label: String, start_after: Option<String>, limit: Option<u32>},
+    HotWalletsByAction {action_id: u64, start_after: Option<String>, limit: Option<u32>},
+    WhitelistedContract {label: String},
+    WhitelistedContracts {start_after: Option<String>, limit: Option<u32>},
+    WhitelistedContractsByAddress {address: String, start_after: Option<String>, limit: Option<u32>},
+    WhitelistDrift {start_after: Option<String>, limit: Option<u32>}, //approved vs deployed code id per whitelisted contract
+    ActionTemplate {id: u64},
+    ActionTemplates {start_after: Option<u64>, limit: Option<u32>},
+    SpendingAllowance {address: String},
+    ActionState {address: String, action_id: u64},
+    LoanConfig {},
+    BondValidators {},
+    Airdrops {start_after: Option<String>, limit: Option<u32>},
+    SwapPairs {start_after: Option<String>, limit: Option<u32>},
+    Cw20Balances {start_after: Option<String>, limit: Option<u32>},
+    WithdrawableUnbonded {},
+    UnbondRequests {},
+    Freeze {},
+    Guardians {},
+    RecoveryProposal {id: u64},
+    RecoveryProposals {start_after: Option<u64>, limit: Option<u32>},
+    Timelock {},
+    QueuedCommand {id: u64},
+    QueuedCommands {start_after: Option<u64>, limit: Option<u32>},
+    BeneficiaryPolicy {},
+    Beneficiaries {start_after: Option<String>, limit: Option<u32>},
+    ActionHistory {hot_wallet: Option<String>, start_after: Option<u64>, limit: Option<u32>}, //journal, oldest first; hot_wallet filters by actor
+    Reserves {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -89,26 +192,409 @@ pub struct HotWallet {
     pub address: String,
     pub label: String,
     pub gas_cooldown: u64,
-    pub gas_tank_max: Uint128,
-    pub whitelisted_messages: Vec<u64>, //cooldown for these too?
+    pub gas_tanks: Vec<GasTank>, //topped up together by FillUpGas
+    pub whitelisted_messages: Vec<u64>, //built-in hot msg ids or action template ids
+    #[serde(default)]
+    pub spending_limits: Vec<SpendingLimit>,
+    #[serde(default)]
+    pub action_limits: Vec<ActionLimit>,
+}
+
+/// hot wallet balance of denom FillUpGas tops up to max; buffer is what the smart wallet keeps of denom.
+/// the denom's reserve applies as well, whichever of the two is larger wins
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GasTank {
+    pub denom: String,
+    pub max: Uint128,
+    pub buffer: Uint128,
+}
+
+/// cooldown (seconds) between calls of a whitelisted msg, and optionally max calls per period (seconds).
+/// one limit per action id; period must be non-zero when max_calls is set
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ActionLimit {
+    pub action_id: u64,
+    pub cooldown: u64,
+    pub max_calls: Option<u64>,
+    pub period: u64,
+}
+
+/// caps how much of denom hot actions may move out of the smart wallet within any rolling window (seconds).
+/// cw20s are capped by using the token contract address as denom. a denom may have one limit per window
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendingLimit {
+    pub denom: String,
+    pub max_amount: Uint128,
+    pub window: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct WhitelistedContract {
     pub address: String,
     pub label: String,
-    pub code_id: u64, //this may be overkill
+    pub code_id: u64, //hot msgs refuse the contract once the deployed code id differs
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
-    pub hot_wallets: Vec<HotWallet>,
     pub cw3_address: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HotWalletsResponse {
+    pub hot_wallets: Vec<HotWallet>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WhitelistedContractsResponse {
     pub whitelisted_contracts: Vec<WhitelistedContract>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct HotWalletStateResponse {
+pub struct ContractDrift {
+    pub label: String,
+    pub address: String,
+    pub code_id: u64, //approved
+    pub live_code_id: Option<u64>, //none if the contract info query failed
+    pub drifted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WhitelistDriftResponse {
+    pub contracts: Vec<ContractDrift>,
+}
+
+/// where a hot wallet's gas tanks stand and what a FillUpGas would do right now
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GasTankStatusResponse {
+    pub address: String,
+    pub cooldown_left: u64, //seconds until FillUpGas is allowed again
+    pub tanks: Vec<GasTankStatus>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GasTankStatus {
+    pub denom: String,
+    pub balance: Uint128, //hot wallet's
+    pub max: Uint128,
+    pub deficit: Uint128,
+    pub tax: Uint128, //deducted from the deficit when it's sent
+    pub refill_funded: bool, //the smart wallet holds the deficit on top of the tank's buffer
+}
+
+/// template for a registry driven hot msg.
+/// msg is the json payload sent to the whitelisted contract; string values of the form "$name"
+/// are replaced by the matching param when the action is run
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ActionTemplate {
+    pub label: String,
+    pub contract_label: String,
+    pub msg: String,
+    pub params: Vec<ActionParamSlot>,
+    pub funds: Option<ActionFunds>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ActionParamSlot {
+    pub name: String,
+    pub param_type: ActionParamType,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionParamType {
+    Uint128,
+    Decimal,
+    U64,
+    String,
+    Address,
+}
+
+/// funds attached to the msg; amount is read from the uint128 param named amount_param
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ActionFunds {
+    pub denom: String,
+    pub max_amount: Uint128,
+    pub amount_param: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ActionParam {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ActionTemplateResponse {
+    pub id: u64,
+    pub template: ActionTemplate,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ActionTemplatesResponse {
+    pub templates: Vec<ActionTemplateResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendingAllowance {
+    pub denom: String,
+    pub window: u64,
+    pub max_amount: Uint128,
+    pub spent: Uint128,
+    pub remaining: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendingAllowanceResponse {
+    pub address: String,
+    pub allowances: Vec<SpendingAllowance>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ActionStateResponse {
+    pub address: String,
+    pub action_id: u64,
+    pub last_execution: Option<u64>,
+    pub period_calls: u64,
+    pub next_allowed: u64,
+}
+
+/// target_ltv is loan / collateral value (at oracle prices) that borrowing and collateral
+/// withdrawal may not go past; RebalanceLoan repays down to safe_ltv
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LoanConfig {
+    pub target_ltv: Decimal256,
+    pub safe_ltv: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LoanConfigResponse {
+    pub loan_config: Option<LoanConfig>,
+}
+
+/// an airdrop contract (MIR, ANC, ...) the wallet may claim from, and optionally the terraswap
+/// pair its token is sold through; swaps fail past max_spread. the contracts are referenced by
+/// whitelisted contract label
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Airdrop {
+    pub label: String,
+    pub airdrop_label: String,
+    pub token_label: String,
+    pub pair_label: Option<String>,
+    pub max_spread: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AirdropsResponse {
+    pub airdrops: Vec<Airdrop>,
+}
+
+/// same shape as terraswap's AssetInfo
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetInfo {
+    Token {contract_addr: String},
+    NativeToken {denom: String},
+}
+
+/// a direction hot wallets may swap in on the whitelisted terraswap pair contract_label;
+/// Swap's max_spread may not go past max_spread, and its belief_price (offer per ask, as terraswap
+/// takes it) has to be within min_belief_price..=max_belief_price. cw20 assets must be whitelisted contracts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapPair {
+    pub label: String,
+    pub contract_label: String,
+    pub offer_asset: AssetInfo,
+    pub ask_asset: AssetInfo,
+    pub max_spread: Decimal,
+    pub min_belief_price: Decimal,
+    pub max_belief_price: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapPairsResponse {
+    pub swap_pairs: Vec<SwapPair>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20Balance {
+    pub token: String,
+    pub balance: Uint128,
+}
+
+/// smart wallet balances of the tracked cw20s
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20BalancesResponse {
+    pub balances: Vec<Cw20Balance>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BondValidatorsResponse {
+    pub validators: Vec<String>,
+}
+
+/// uluna the bluna hub would pay out on BlunaWithdrawUnbonded right now
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WithdrawableUnbondedResponse {
+    pub withdrawable: Uint128,
+}
+
+/// the smart wallet's pending unbonds at the bluna hub as (batch id, bluna amount)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondRequestsResponse {
+    pub requests: Vec<(u64, Uint128)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FreezeInfo {
+    pub reason: String,
+    pub initiator: Addr,
+    pub frozen_at: u64,
+}
+
+/// freeze is None while the wallet is not frozen
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FreezeResponse {
+    pub freeze: Option<FreezeInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GuardiansResponse {
+    pub guardians: Vec<Addr>,
+    pub threshold: u64,
+    pub delay: u64,
+}
+
+/// pending replacement of cw3_address. executable_at is set once threshold guardians approved;
+/// the current multisig can veto until then
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RecoveryProposal {
+    pub id: u64,
+    pub new_cw3_address: Addr,
+    pub approvals: Vec<Addr>,
+    pub proposed_at: u64,
+    pub executable_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RecoveryProposalsResponse {
+    pub proposals: Vec<RecoveryProposal>,
+}
+
+/// multisig commands are queued for delay (seconds) when they move at least the threshold
+/// amount of a denom, or when their kind is listed. cw20 transfers, sends, burns and allowances
+/// (WithdrawCw20 included) are matched against thresholds whose denom is the token address.
+/// UpdateTimelock always waits it out, and so do the config updates that widen what hot wallets
+/// can reach: UpsertHot, ReplaceContractWhitelist, Add/UpdateWhitelistedContract,
+/// Add/UpdateActionTemplate, UpsertSwapPair and UpsertAirdrop. removals apply right away.
+/// replacing the multisig, by itself or by recovery, drops every queued command
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimelockConfig {
+    pub delay: u64,
+    pub thresholds: Vec<Coin>,
+    pub kinds: Vec<TimelockKind>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelockKind {
+    BankSend,
+    WasmExecute,
+    WasmInstantiate,
+    WasmMigrate,
+    WasmAdmin, //update and clear admin
+    Other, //any other CosmosMsg
+    ReplaceMultisig,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelockedCommand {
+    Execute {command: CosmosMsg<Empty>},
+    ReplaceMultisig {address: Addr},
+    UpdateTimelock {timelock: Option<TimelockConfig>},
+    UpdateConfig {msg: Box<ExecuteMsg>}, //one of the config updates the timelock holds back
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueuedCommand {
+    pub id: u64,
+    pub command: TimelockedCommand,
+    pub queued_at: u64,
+    pub eta: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimelockResponse {
+    pub timelock: Option<TimelockConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueuedCommandsResponse {
+    pub commands: Vec<QueuedCommand>,
+}
+
+/// while set, bank sends, cw20 transfers, sends and allowances and coins attached to contract calls
+/// leaving the wallet, from multisig commands and every hot action, may only go to beneficiaries or
+/// whitelisted contracts. a new beneficiary, or a contract whitelisted under the policy, is allowed
+/// delay (seconds) after being added
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BeneficiaryPolicy {
+    pub delay: u64,
+}
+
+/// loosened policy waiting out the delay of the current one
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingBeneficiaryPolicy {
+    pub policy: Option<BeneficiaryPolicy>,
+    pub effective_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BeneficiaryPolicyResponse {
+    pub policy: Option<BeneficiaryPolicy>,
+    pub pending: Option<PendingBeneficiaryPolicy>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Beneficiary {
     pub address: String,
-    pub gas_time_left: u64,
-}
\ No newline at end of file
+    pub active_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BeneficiariesResponse {
+    pub beneficiaries: Vec<Beneficiary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ActorRole {
+    HotWallet,
+    Multisig,
+    Guardian,
+    Anyone, //permissionless ExecuteRecovery and ExecuteQueued
+}
+
+/// one successful execute by a hot wallet, the multisig or a guardian, with the attributes it emitted.
+/// replies finishing a hot action (compound deposit, swap receipt) get their own entry under that hot wallet
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JournalEntry {
+    pub id: u64,
+    pub actor: String,
+    pub role: ActorRole,
+    pub action: String,
+    pub attributes: Vec<Attribute>,
+    pub height: u64,
+    pub time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ActionHistoryResponse {
+    pub entries: Vec<JournalEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReservesResponse {
+    pub reserves: Vec<Coin>,
+}